@@ -0,0 +1,52 @@
+//! Pausing of the local block authoring, without restarting the node.
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use sp_consensus::SyncOracle;
+
+/// Pauses and resumes the local Aura authoring. Clones share the same switch.
+#[derive(Clone, Default)]
+pub struct AuthoringSwitch(Arc<AtomicBool>);
+
+impl AuthoringSwitch {
+	/// Stop claiming slots. The node keeps following the chain, as an observer.
+	pub fn pause(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	/// Claim slots again.
+	pub fn resume(&self) {
+		self.0.store(false, Ordering::Relaxed);
+	}
+
+	/// Whether authoring is paused.
+	pub fn is_paused(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+
+	/// Wrap the sync oracle of the authoring task, so that it skips slots while paused.
+	pub fn sync_oracle<SO>(&self, inner: SO) -> PausableSyncOracle<SO> {
+		PausableSyncOracle { inner, switch: self.clone() }
+	}
+}
+
+/// A sync oracle reporting a major sync while authoring is paused, which the slot worker doesn't
+/// claim slots during.
+#[derive(Clone)]
+pub struct PausableSyncOracle<SO> {
+	inner: SO,
+	switch: AuthoringSwitch,
+}
+
+impl<SO: SyncOracle> SyncOracle for PausableSyncOracle<SO> {
+	fn is_major_syncing(&self) -> bool {
+		self.switch.is_paused() || self.inner.is_major_syncing()
+	}
+
+	fn is_offline(&self) -> bool {
+		self.inner.is_offline()
+	}
+}
@@ -5,7 +5,7 @@
 use crate::service::FullClient;
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{Address, Balance, BalancesCall, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use sp_core::{Encode, Pair};
@@ -54,16 +54,18 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for RemarkBuilder {
 
 /// Generates `Balances::TransferKeepAlive` extrinsics for the benchmarks.
 ///
+/// The destination may be given by account id or by account index.
+///
 /// Note: Should only be used for benchmarking.
 pub struct TransferKeepAliveBuilder {
 	client: Arc<FullClient>,
-	dest: AccountId,
+	dest: Address,
 	value: Balance,
 }
 
 impl TransferKeepAliveBuilder {
 	/// Creates a new [`Self`] from the given client.
-	pub fn new(client: Arc<FullClient>, dest: AccountId, value: Balance) -> Self {
+	pub fn new(client: Arc<FullClient>, dest: Address, value: Balance) -> Self {
 		Self { client, dest, value }
 	}
 }
@@ -83,7 +85,7 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 			self.client.as_ref(),
 			acc,
 			BalancesCall::transfer_keep_alive {
-				dest: self.dest.clone().into(),
+				dest: self.dest.clone(),
 				value: self.value.into(),
 			}
 			.into(),
@@ -144,7 +146,7 @@ pub fn create_benchmark_extrinsic(
 
 	runtime::UncheckedExtrinsic::new_signed(
 		call.clone(),
-		sp_runtime::AccountId32::from(sender.public()).into(),
+		Address::Id(sender.public().into()),
 		runtime::Signature::Sr25519(signature.clone()),
 		extra.clone(),
 	)
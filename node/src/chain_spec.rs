@@ -1,15 +1,23 @@
+pub mod generator;
+pub mod genesis;
+
+use codec::Encode;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	opaque::{Block, Header, SessionKeys},
+	AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber, EVMConfig, EthereumConfig,
+	GenesisConfig, GrandpaConfig, IndicesConfig, Inflation, Perbill, RewardsConfig, SessionConfig,
+	Signature, StakerStatus, StakingConfig, SudoConfig, SystemConfig, TemplateModuleConfig,
+	VestingConfig, DAYS, SS58_FORMAT, TOKEN_DECIMALS, TOKEN_SYMBOL, WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_service::{ChainType, Properties};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{crypto::DeriveJunction, sr25519, Pair, Public, H160, U256};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
-
-// The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+use sp_runtime::{
+	traits::{BlakeTwo256, Block as BlockT, Hash, Header as HeaderT, IdentifyAccount, Verify},
+	BuildStorage, StateVersion,
+};
+use std::{collections::BTreeMap, iter, str::FromStr};
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
@@ -23,6 +31,12 @@ pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Pu
 
 type AccountPublic = <Signature as Verify>::Signer;
 
+/// A genesis vesting schedule: `(who, start block, unlock period in blocks, liquid balance)`.
+///
+/// `who` must be endowed. Everything above the liquid balance is locked until the start block,
+/// then unlocks linearly, by the same amount each block, over the unlock period.
+pub type VestingSchedule = (AccountId, BlockNumber, BlockNumber, Balance);
+
 /// Generate an account ID from seed.
 pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
 where
@@ -31,9 +45,33 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// The Ethereum account of the development signer (`--enable-dev-signer`).
+const DEV_EVM_ACCOUNT: &str = "6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+
+/// The amount each genesis staker bonds.
+pub const STASH: Balance = 1 << 58;
+
+/// Generate the stash and controller accounts and the session keys of a validator.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AccountId, AuraId, GrandpaId) {
+	(
+		get_account_id_from_seed::<sr25519::Public>(&format!("{}//stash", s)),
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<AuraId>(s),
+		get_from_seed::<GrandpaId>(s),
+	)
+}
+
+/// The properties wallets read: the token symbol and decimals, and the SS58 format.
+pub fn properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), SS58_FORMAT.into());
+	properties
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
@@ -48,8 +86,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
-				// Initial PoA authorities
+				// Initial validators
 				vec![authority_keys_from_seed("Alice")],
+				// Initial nominators
+				vec![],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				// Pre-funded accounts
@@ -59,6 +99,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Pre-funded EVM accounts
+				vec![H160::from_str(DEV_EVM_ACCOUNT).expect("internal H160 is valid; qed")],
+				// Vesting schedules
+				vec![],
 				true,
 			)
 		},
@@ -70,7 +114,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		None,
 		None,
 		// Properties
-		None,
+		Some(properties()),
 		// Extensions
 		None,
 	))
@@ -88,8 +132,78 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
-				// Initial PoA authorities
+				// Initial validators
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+				// Initial nominators
+				vec![],
+				// Sudo account
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Pre-funded accounts
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+					get_account_id_from_seed::<sr25519::Public>("Dave"),
+					get_account_id_from_seed::<sr25519::Public>("Eve"),
+					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+				],
+				// Pre-funded EVM accounts
+				vec![],
+				// Vesting schedules: half of Ferdie's endowment is locked for a day, then unlocks
+				// linearly over the following month.
+				vec![(
+					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+					DAYS,
+					30 * DAYS,
+					1 << 59,
+				)],
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		None,
+		// Properties
+		Some(properties()),
+		// Extensions
+		None,
+	))
+}
+
+pub fn npos_testnet_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"NPoS Testnet",
+		// ID
+		"npos_testnet",
+		ChainType::Local,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				// Initial validators
+				vec![
+					authority_keys_from_seed("Alice"),
+					authority_keys_from_seed("Bob"),
+					authority_keys_from_seed("Charlie"),
+				],
+				// Initial nominators, backing every initial validator
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+				],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				// Pre-funded accounts
@@ -107,6 +221,10 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Pre-funded EVM accounts
+				vec![],
+				// Vesting schedules
+				vec![],
 				true,
 			)
 		},
@@ -116,9 +234,74 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		None,
 		// Protocol ID
 		None,
+		None,
 		// Properties
+		Some(properties()),
+		// Extensions
+		None,
+	))
+}
+
+/// The number of pre-funded accounts of the bench chain.
+const BENCH_ACCOUNTS: u32 = 10_000;
+
+/// The accounts `//bench//0` to `//bench//{count - 1}`, derived from the development phrase.
+fn bench_accounts(count: u32) -> Vec<AccountId> {
+	let root = sr25519::Pair::from_string("//bench", None).expect("static values are valid; qed");
+	(0..count as u64)
+		.map(|index| {
+			let (pair, _) = root
+				.derive(iter::once(DeriveJunction::hard(index)), None)
+				.expect("hard derivations of sr25519 keys don't fail; qed");
+			AccountPublic::from(pair.public()).into_account()
+		})
+		.collect()
+}
+
+/// A development chain with a large state, for `benchmark block` and `benchmark overhead` runs
+/// and sync tests: Alice validates, and the accounts `//bench//0` to `//bench//9999` are
+/// pre-funded, each with a value of its number in the template pallet.
+pub fn bench_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Bench",
+		// ID
+		"bench",
+		ChainType::Development,
+		move || {
+			let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+			let mut balances = vec![
+				(alice.clone(), 1 << 60),
+				(get_account_id_from_seed::<sr25519::Public>("Alice//stash"), 1 << 60),
+			];
+			let bench_accounts = bench_accounts(BENCH_ACCOUNTS);
+			balances.extend(bench_accounts.iter().map(|account| (account.clone(), 1 << 60)));
+
+			genesis_config(
+				wasm_binary,
+				Genesis {
+					authorities: vec![authority_keys_from_seed("Alice")],
+					nominators: vec![],
+					sudo: Some(alice),
+					balances,
+					evm_balances: vec![],
+					vesting: vec![],
+					somethings: bench_accounts.into_iter().zip(0..).collect(),
+					inflation: default_inflation(),
+				},
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
 		None,
 		None,
+		// Properties
+		Some(properties()),
 		// Extensions
 		None,
 	))
@@ -127,30 +310,258 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(AccountId, AccountId, AuraId, GrandpaId)>,
+	initial_nominators: Vec<AccountId>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	endowed_evm_accounts: Vec<H160>,
+	vesting: Vec<VestingSchedule>,
 	_enable_println: bool,
 ) -> GenesisConfig {
+	genesis_config(
+		wasm_binary,
+		Genesis {
+			authorities: initial_authorities,
+			nominators: initial_nominators,
+			sudo: Some(root_key),
+			// Configure endowed accounts with initial balance of 1 << 60.
+			balances: endowed_accounts.into_iter().map(|k| (k, 1 << 60)).collect(),
+			evm_balances: endowed_evm_accounts.into_iter().map(|k| (k, 1 << 60)).collect(),
+			vesting,
+			somethings: vec![],
+			inflation: default_inflation(),
+		},
+	)
+}
+
+/// Block rewards start at 5% of the issuance a year and shrink by a tenth every year, down to 1%.
+/// Block authors and stakers share these rewards, so this is the whole inflation of the chain.
+pub fn default_inflation() -> Inflation {
+	Inflation::Decaying {
+		initial: Perbill::from_percent(5),
+		decay: Perbill::from_percent(10),
+		floor: Perbill::from_percent(1),
+	}
+}
+
+/// The genesis state of a chain.
+#[derive(Clone)]
+pub struct Genesis {
+	/// The stash and controller accounts and the session keys of the validators.
+	pub authorities: Vec<(AccountId, AccountId, AuraId, GrandpaId)>,
+	/// The stash accounts of the nominators, backing every validator.
+	pub nominators: Vec<AccountId>,
+	/// The sudo account.
+	pub sudo: Option<AccountId>,
+	/// The pre-funded accounts, with their balance.
+	pub balances: Vec<(AccountId, Balance)>,
+	/// The pre-funded EVM accounts, with their balance.
+	pub evm_balances: Vec<(H160, Balance)>,
+	/// The vesting schedules of pre-funded accounts.
+	pub vesting: Vec<VestingSchedule>,
+	/// The values of accounts in the template pallet.
+	pub somethings: Vec<(AccountId, u32)>,
+	/// How block rewards evolve.
+	pub inflation: Inflation,
+}
+
+/// The genesis config of the pallets for `genesis`.
+pub fn genesis_config(wasm_binary: &[u8], genesis: Genesis) -> GenesisConfig {
+	let Genesis {
+		authorities,
+		nominators,
+		sudo,
+		balances,
+		evm_balances,
+		vesting,
+		somethings,
+		inflation,
+	} = genesis;
+	let validators = authorities
+		.iter()
+		.map(|x| (x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator));
+	let targets: Vec<_> = authorities.iter().map(|x| x.0.clone()).collect();
+	let nominators = nominators
+		.into_iter()
+		.map(|x| (x.clone(), x, STASH, StakerStatus::Nominator(targets.clone())));
+	let stakers = validators.chain(nominators).collect::<Vec<_>>();
+
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 		},
-		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
-		},
-		aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
-		},
-		grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
-		},
+		balances: BalancesConfig { balances },
+		// Aura and GRANDPA authorities are set by the session pallet from the session keys below.
+		aura: AuraConfig { authorities: vec![] },
+		grandpa: GrandpaConfig { authorities: vec![] },
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: sudo,
 		},
 		transaction_payment: Default::default(),
+		evm: EVMConfig {
+			accounts: evm_balances
+				.into_iter()
+				.map(|(address, balance)| {
+					let account = pallet_evm::GenesisAccount {
+						nonce: U256::zero(),
+						balance: U256::from(balance),
+						storage: BTreeMap::new(),
+						code: vec![],
+					};
+					(address, account)
+				})
+				.collect(),
+		},
+		ethereum: EthereumConfig {},
+		vesting: VestingConfig { vesting },
+		staking: StakingConfig {
+			validator_count: authorities.len() as u32,
+			minimum_validator_count: authorities.len() as u32,
+			invulnerables: authorities.iter().map(|x| x.0.clone()).collect(),
+			slash_reward_fraction: Perbill::from_percent(10),
+			stakers,
+			..Default::default()
+		},
+		rewards: RewardsConfig { inflation },
+		session: SessionConfig {
+			keys: authorities
+				.iter()
+				.map(|x| (x.0.clone(), x.0.clone(), session_keys(x.2.clone(), x.3.clone())))
+				.collect(),
+		},
+		template_module: TemplateModuleConfig { somethings },
+		indices: IndicesConfig { indices: vec![] },
+		treasury: Default::default(),
+	}
+}
+
+/// The genesis block of `spec`, as nodes build it.
+pub fn genesis_block(spec: &dyn sc_service::ChainSpec) -> Result<Block, String> {
+	let storage = spec.build_storage()?;
+	let state_version = node_template_runtime::VERSION.state_version();
+	let child_roots = storage.children_default.values().map(|child| {
+		let root = BlakeTwo256::trie_root(child.data.clone().into_iter().collect(), state_version);
+		(child.child_info.prefixed_storage_key().into_inner(), root.encode())
+	});
+	let state_root =
+		BlakeTwo256::trie_root(storage.top.into_iter().chain(child_roots).collect(), state_version);
+	let extrinsics_root = BlakeTwo256::trie_root(Vec::new(), StateVersion::V0);
+
+	let header =
+		Header::new(0, extrinsics_root, state_root, Default::default(), Default::default());
+	Ok(Block::new(header, Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{metadata::RuntimeMetadata, traits::Get};
+	use node_template_runtime::{Runtime, UNIT};
+	use serde_json::{json, Value};
+	use sp_core::{blake2_256, hexdisplay::HexDisplay, twox_128};
+	use std::{collections::HashMap, fs, path::Path};
+
+	/// The `Pallet.Item` names of the storage items of the runtime, by key prefix.
+	fn storage_items() -> HashMap<Vec<u8>, String> {
+		let metadata = match Runtime::metadata().1 {
+			RuntimeMetadata::V14(metadata) => metadata,
+			_ => panic!("The runtime has V14 metadata"),
+		};
+		let mut items = HashMap::new();
+		for storage in metadata.pallets.iter().filter_map(|pallet| pallet.storage.as_ref()) {
+			for entry in &storage.entries {
+				let prefix =
+					[twox_128(storage.prefix.as_bytes()), twox_128(entry.name.as_bytes())].concat();
+				items.insert(prefix, format!("{}.{}", storage.prefix, entry.name));
+			}
+		}
+		items
+	}
+
+	/// The genesis hash and state root of `spec`, and the hash of the value of every storage key,
+	/// by item, to compare with its golden file.
+	fn genesis_summary(spec: &ChainSpec) -> Value {
+		let storage = spec.build_storage().unwrap();
+		let genesis = genesis_block(spec).unwrap();
+		let names = storage_items();
+
+		let mut items: BTreeMap<String, serde_json::Map<String, Value>> = BTreeMap::new();
+		for (key, value) in storage.top {
+			let name = match key.get(..32).and_then(|prefix| names.get(prefix)) {
+				Some(name) => name.clone(),
+				// Well-known keys, e.g. `:code`.
+				None if key.starts_with(b":") => String::from_utf8_lossy(&key).into_owned(),
+				None => format!("0x{}", HexDisplay::from(&key)),
+			};
+			let hash = format!("0x{}", HexDisplay::from(&blake2_256(&value)));
+			items
+				.entry(name)
+				.or_default()
+				.insert(format!("0x{}", HexDisplay::from(&key)), hash.into());
+		}
+
+		json!({
+			"genesisHash": genesis.header().hash(),
+			"stateRoot": genesis.header().state_root(),
+			"storage": items,
+		})
+	}
+
+	#[test]
+	fn properties_match_the_runtime() {
+		let properties = properties();
+		let ss58_prefix: u16 = <Runtime as frame_system::Config>::SS58Prefix::get();
+
+		assert_eq!(properties["ss58Format"], ss58_prefix);
+		assert_eq!(properties["tokenSymbol"], TOKEN_SYMBOL);
+		assert_eq!(properties["tokenDecimals"], TOKEN_DECIMALS);
+		assert_eq!(UNIT, 10u128.pow(TOKEN_DECIMALS.into()));
+	}
+
+	#[test]
+	fn bench_accounts_are_derived_from_the_development_phrase() {
+		let accounts = bench_accounts(50);
+		assert_eq!(accounts[42], get_account_id_from_seed::<sr25519::Public>("bench//42"));
+		assert_eq!(accounts, bench_accounts(50));
+	}
+
+	/// Compare the genesis of every preset with its golden file in `res/genesis`. Run with
+	/// `BLESS=1` to update the golden files after an intended change.
+	#[test]
+	fn genesis_matches_the_golden_files() {
+		let presets = [
+			("dev", development_config()),
+			("local", local_testnet_config()),
+			("npos", npos_testnet_config()),
+			("bench", bench_config()),
+		];
+		let bless = std::env::var_os("BLESS").is_some();
+		let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("res").join("genesis");
+
+		let mut changed = Vec::new();
+		for (name, spec) in presets {
+			let summary = genesis_summary(&spec.unwrap());
+			let path = dir.join(format!("{}.json", name));
+			if bless {
+				fs::create_dir_all(&dir).unwrap();
+				fs::write(&path, serde_json::to_string_pretty(&summary).unwrap() + "\n").unwrap();
+				continue
+			}
+
+			let golden = fs::read(&path)
+				.ok()
+				.and_then(|golden| serde_json::from_slice::<Value>(&golden).ok());
+			if golden.as_ref() != Some(&summary) {
+				eprintln!("The genesis of `{}` is now {:#}", name, summary);
+				changed.push(name);
+			}
+		}
+		assert!(
+			changed.is_empty(),
+			"The genesis of {:?} changed. Run the test with `BLESS=1` if that is intended.",
+			changed,
+		);
 	}
 }
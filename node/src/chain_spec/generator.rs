@@ -0,0 +1,409 @@
+//! The `generate-spec` command: a chain spec from a declarative config file, e.g.
+//!
+//! ```toml
+//! name = "Staging Testnet"
+//! id = "staging_testnet"
+//! chainType = "Live"
+//! protocolId = "staging"
+//! bootNodes = ["/dns/boot.example.com/tcp/30333/p2p/12D3KooW..."]
+//! telemetryEndpoints = [["/dns/telemetry.polkadot.io/tcp/443/x-parity-wss/%2Fsubmit%2F", 0]]
+//! sudo = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//!
+//! [[authorities]]
+//! stash = "5GNJqTPyNqANBkUVMN1LPPrxXnFouWXoe2wNSmmEoLctxiZY"
+//! controller = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! aura = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! grandpa = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu"
+//!
+//! [[balances]]
+//! account = "5GNJqTPyNqANBkUVMN1LPPrxXnFouWXoe2wNSmmEoLctxiZY"
+//! balance = 1152921504606846976
+//!
+//! [genesis]
+//! inflation = { Fixed = 20000000 }
+//! ```
+//!
+//! Accounts and keys are SS58 addresses, EVM accounts hex addresses, and balances are in the
+//! smallest unit. JSON config files take the same fields.
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs,
+	path::{Path, PathBuf},
+};
+
+use node_template_runtime::{
+	AccountId, Balance, BlockNumber, Inflation, EXISTENTIAL_DEPOSIT, MAX_AUTHORITIES, WASM_BINARY,
+};
+use sc_service::{config::MultiaddrWithPeerId, ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use serde::Deserialize;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::H160;
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+
+use super::{default_inflation, genesis_config, properties, ChainSpec, Genesis, STASH};
+
+/// Build a chain spec from a declarative config file.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct GenerateSpecCmd {
+	/// The config file of the chain, in TOML, or in JSON with a `.json` extension.
+	#[arg(value_name = "PATH")]
+	pub config: PathBuf,
+
+	/// Emit the raw chain spec, with the genesis storage instead of the pallet configs.
+	#[arg(long)]
+	pub raw: bool,
+
+	/// Write the chain spec to a file instead of the standard output.
+	#[arg(long, short, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+}
+
+impl GenerateSpecCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let config = SpecConfig::load(&self.config).map_err(sc_cli::Error::Input)?;
+		let spec = config.chain_spec().map_err(sc_cli::Error::Input)?;
+		let json = spec.as_json(self.raw).map_err(sc_cli::Error::Input)?;
+		match &self.output {
+			Some(path) => fs::write(path, json)?,
+			None => println!("{}", json),
+		}
+		Ok(())
+	}
+}
+
+/// A validator.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Authority {
+	/// The stash account, which bonds.
+	pub stash: AccountId,
+	/// The controller account.
+	pub controller: AccountId,
+	/// The Aura key.
+	pub aura: AuraId,
+	/// The GRANDPA key.
+	pub grandpa: GrandpaId,
+}
+
+/// A pre-funded account.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowment {
+	/// The account.
+	pub account: AccountId,
+	/// Its balance.
+	pub balance: Balance,
+}
+
+/// A pre-funded EVM account.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EvmEndowment {
+	/// The account.
+	pub address: H160,
+	/// Its balance.
+	pub balance: Balance,
+}
+
+/// The vesting schedule of a pre-funded account, see [`super::VestingSchedule`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+	/// The account.
+	pub account: AccountId,
+	/// The block the balance starts unlocking at.
+	pub start: BlockNumber,
+	/// The number of blocks the balance unlocks over.
+	pub period: BlockNumber,
+	/// The balance not locked.
+	pub liquid: Balance,
+}
+
+/// The genesis config of the pallets not covered by the rest of the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PalletGenesis {
+	/// The pre-funded EVM accounts.
+	#[serde(default)]
+	pub evm_balances: Vec<EvmEndowment>,
+	/// The vesting schedules.
+	#[serde(default)]
+	pub vesting: Vec<Vesting>,
+	/// How block rewards evolve, 5% of the issuance a year decaying to 1% by default.
+	pub inflation: Option<Inflation>,
+}
+
+/// The config file of a chain.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SpecConfig {
+	/// The name of the chain.
+	pub name: String,
+	/// The id of the chain, which also names its directory.
+	pub id: String,
+	/// The type of the chain, `Live` by default.
+	#[serde(default = "live")]
+	pub chain_type: ChainType,
+	/// The nodes to connect to first.
+	#[serde(default)]
+	pub boot_nodes: Vec<MultiaddrWithPeerId>,
+	/// The telemetry servers, with their verbosity.
+	pub telemetry_endpoints: Option<TelemetryEndpoints>,
+	/// The network protocol id.
+	pub protocol_id: Option<String>,
+	/// The properties wallets read, e.g. `tokenSymbol` and `tokenDecimals`, the ones of the
+	/// runtime by default.
+	pub properties: Option<Properties>,
+	/// The validators.
+	pub authorities: Vec<Authority>,
+	/// The stash accounts of the nominators, backing every validator.
+	#[serde(default)]
+	pub nominators: Vec<AccountId>,
+	/// The sudo account, if any.
+	pub sudo: Option<AccountId>,
+	/// The pre-funded accounts.
+	pub balances: Vec<Endowment>,
+	/// The genesis config of the other pallets.
+	#[serde(default)]
+	pub genesis: PalletGenesis,
+}
+
+fn live() -> ChainType {
+	ChainType::Live
+}
+
+impl SpecConfig {
+	/// Load the config file at `path`.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let config = fs::read_to_string(path).map_err(|e| e.to_string())?;
+		let config = match path.extension().and_then(|extension| extension.to_str()) {
+			Some("json") => serde_json::from_str(&config).map_err(|e| e.to_string()),
+			_ => toml::from_str(&config).map_err(|e| e.to_string()),
+		};
+		config.map_err(|e| format!("Invalid chain spec config {}: {}", path.display(), e))
+	}
+
+	/// Check that the genesis state is consistent: the accounts are funded above the existential
+	/// deposit, the stakers can bond, and the validators are few enough for the runtime and use
+	/// distinct keys.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.authorities.is_empty() {
+			return Err("At least one authority is needed".into())
+		}
+		if self.authorities.len() > MAX_AUTHORITIES as usize {
+			return Err(format!(
+				"There are {} authorities, but the runtime supports at most {}",
+				self.authorities.len(),
+				MAX_AUTHORITIES,
+			))
+		}
+
+		let mut balances = HashMap::new();
+		for Endowment { account, balance } in &self.balances {
+			if *balance < EXISTENTIAL_DEPOSIT {
+				return Err(format!(
+					"The balance of {} is below the existential deposit of {}",
+					account, EXISTENTIAL_DEPOSIT,
+				))
+			}
+			if balances.insert(account, *balance).is_some() {
+				return Err(format!("{} is endowed twice", account))
+			}
+		}
+
+		let stashes = self.authorities.iter().map(|authority| &authority.stash);
+		for stash in stashes.chain(&self.nominators) {
+			if balances.get(stash).map_or(true, |balance| *balance < STASH) {
+				return Err(format!("The staker {} needs a balance of {} to bond", stash, STASH))
+			}
+		}
+		let mut stashes = HashSet::new();
+		let mut aura_keys = HashSet::new();
+		let mut grandpa_keys = HashSet::new();
+		for authority in &self.authorities {
+			if !stashes.insert(&authority.stash) {
+				return Err(format!("The stash {} is used twice", authority.stash))
+			}
+			if !aura_keys.insert(&authority.aura) {
+				return Err(format!("The Aura key {} is used twice", authority.aura))
+			}
+			if !grandpa_keys.insert(&authority.grandpa) {
+				return Err(format!("The GRANDPA key {} is used twice", authority.grandpa))
+			}
+		}
+
+		for vesting in &self.genesis.vesting {
+			match balances.get(&vesting.account) {
+				Some(balance) if vesting.liquid <= *balance => (),
+				Some(_) => return Err(format!("{} vests more than its balance", vesting.account)),
+				None => return Err(format!("{} vests without being endowed", vesting.account)),
+			}
+		}
+
+		Ok(())
+	}
+
+	/// The genesis state of the chain.
+	pub fn genesis(&self) -> Genesis {
+		Genesis {
+			authorities: self
+				.authorities
+				.iter()
+				.map(|authority| {
+					let Authority { stash, controller, aura, grandpa } = authority.clone();
+					(stash, controller, aura, grandpa)
+				})
+				.collect(),
+			nominators: self.nominators.clone(),
+			sudo: self.sudo.clone(),
+			balances: self
+				.balances
+				.iter()
+				.map(|endowment| (endowment.account.clone(), endowment.balance))
+				.collect(),
+			evm_balances: self
+				.genesis
+				.evm_balances
+				.iter()
+				.map(|endowment| (endowment.address, endowment.balance))
+				.collect(),
+			vesting: self
+				.genesis
+				.vesting
+				.iter()
+				.map(|vesting| {
+					(vesting.account.clone(), vesting.start, vesting.period, vesting.liquid)
+				})
+				.collect(),
+			somethings: Vec::new(),
+			inflation: self.genesis.inflation.unwrap_or_else(default_inflation),
+		}
+	}
+
+	/// The chain spec, once validated.
+	pub fn chain_spec(&self) -> Result<ChainSpec, String> {
+		self.validate()?;
+		let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+		let genesis = self.genesis();
+
+		Ok(ChainSpec::from_genesis(
+			&self.name,
+			&self.id,
+			self.chain_type.clone(),
+			move || genesis_config(wasm_binary, genesis.clone()),
+			self.boot_nodes.clone(),
+			self.telemetry_endpoints.clone(),
+			self.protocol_id.as_deref(),
+			None,
+			Some(self.properties.clone().unwrap_or_else(properties)),
+			None,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::crypto::Ss58Codec;
+	use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+	/// A config with Alice as the validator, staking from Charlie, and `balances` after theirs.
+	fn config(balances: &str) -> String {
+		format!(
+			r#"
+				name = "Test"
+				id = "test"
+				sudo = "{alice}"
+
+				[[authorities]]
+				stash = "{charlie}"
+				controller = "{alice}"
+				aura = "{alice}"
+				grandpa = "{alice_grandpa}"
+
+				[[balances]]
+				account = "{charlie}"
+				balance = {stash}
+
+				[[balances]]
+				account = "{alice}"
+				balance = {existential_deposit}
+
+				{balances}
+			"#,
+			alice = Sr25519Keyring::Alice.to_account_id(),
+			charlie = Sr25519Keyring::Charlie.to_account_id(),
+			alice_grandpa = Ed25519Keyring::Alice.public().to_ss58check(),
+			stash = STASH,
+			existential_deposit = EXISTENTIAL_DEPOSIT,
+			balances = balances,
+		)
+	}
+
+	fn validate(config: &str) -> Result<(), String> {
+		toml::from_str::<SpecConfig>(config).map_err(|e| e.to_string())?.validate()
+	}
+
+	#[test]
+	fn valid_configs_parse() {
+		let config: SpecConfig = toml::from_str(&config(
+			r#"
+				[genesis]
+				inflation = { Fixed = 20000000 }
+			"#,
+		))
+		.unwrap();
+		assert_eq!(config.validate(), Ok(()));
+		assert_eq!(config.chain_type, ChainType::Live);
+
+		let genesis = config.genesis();
+		assert_eq!(genesis.authorities.len(), 1);
+		assert_eq!(genesis.sudo, Some(Sr25519Keyring::Alice.to_account_id()));
+		assert_eq!(genesis.balances.len(), 2);
+		assert_eq!(genesis.inflation, Inflation::Fixed(sp_runtime::Perbill::from_percent(2)));
+	}
+
+	#[test]
+	fn balances_are_checked() {
+		let bob = Sr25519Keyring::Bob.to_account_id();
+		let poor =
+			format!("[[balances]]\naccount = \"{}\"\nbalance = {}", bob, EXISTENTIAL_DEPOSIT - 1);
+		assert!(validate(&config(&poor)).unwrap_err().contains("existential"));
+
+		let mut nominated: SpecConfig = toml::from_str(&config("")).unwrap();
+		nominated.nominators.push(bob.clone());
+		assert!(nominated.validate().unwrap_err().contains("to bond"));
+
+		let vesting = format!(
+			"[[genesis.vesting]]\naccount = \"{}\"\nstart = 0\nperiod = 10\nliquid = 0",
+			bob,
+		);
+		assert!(validate(&config(&vesting)).unwrap_err().contains("without being endowed"));
+	}
+
+	#[test]
+	fn keys_are_checked() {
+		let config = config("");
+		let grandpa = Ed25519Keyring::Alice.public().to_ss58check();
+		// The same key with another last character, which breaks its checksum.
+		let mut invalid = grandpa.clone();
+		let last = if invalid.pop() == Some('2') { '3' } else { '2' };
+		invalid.push(last);
+		assert!(validate(&config.replace(&grandpa, &invalid)).is_err());
+
+		let mut config: SpecConfig = toml::from_str(&config).unwrap();
+		config.authorities.push(config.authorities[0].clone());
+		assert!(config.validate().unwrap_err().contains("used twice"));
+	}
+
+	#[test]
+	fn authorities_are_bounded() {
+		let mut config: SpecConfig = toml::from_str(&config("")).unwrap();
+		let authority = config.authorities[0].clone();
+		config.authorities.resize(MAX_AUTHORITIES as usize + 1, authority);
+		assert!(config.validate().unwrap_err().contains("supports at most"));
+	}
+}
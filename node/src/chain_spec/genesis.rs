@@ -0,0 +1,75 @@
+//! The `export-genesis-state` and `export-genesis-wasm` commands: the genesis head and code of a
+//! chain, for the tools that need them without running a node.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use codec::Encode;
+use sc_cli::SharedParams;
+use sc_service::ChainSpec;
+use sp_core::{hexdisplay::HexDisplay, storage::well_known_keys};
+use sp_runtime::{traits::Block as BlockT, BuildStorage};
+
+use super::genesis_block;
+
+/// Write `bytes` to `path`, or to the standard output, in hex unless `raw`.
+fn write(bytes: &[u8], path: Option<&PathBuf>, raw: bool) -> sc_cli::Result<()> {
+	let output =
+		if raw { bytes.to_vec() } else { format!("0x{}", HexDisplay::from(&bytes)).into_bytes() };
+	match path {
+		Some(path) => fs::write(path, output)?,
+		None => std::io::stdout().write_all(&output)?,
+	}
+	Ok(())
+}
+
+/// Export the SCALE-encoded header of the genesis block.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportGenesisStateCmd {
+	/// The file to write to, or the standard output if unspecified.
+	#[arg(value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	/// Write in binary rather than in hex.
+	#[arg(short, long)]
+	pub raw: bool,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl ExportGenesisStateCmd {
+	/// Run the command for `spec`.
+	pub fn run(&self, spec: &dyn ChainSpec) -> sc_cli::Result<()> {
+		let genesis = genesis_block(spec).map_err(sc_cli::Error::Input)?;
+		write(&genesis.header().encode(), self.output.as_ref(), self.raw)
+	}
+}
+
+/// Export the runtime code of the genesis block.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportGenesisWasmCmd {
+	/// The file to write to, or the standard output if unspecified.
+	#[arg(value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	/// Write in binary rather than in hex.
+	#[arg(short, long)]
+	pub raw: bool,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl ExportGenesisWasmCmd {
+	/// Run the command for `spec`.
+	pub fn run(&self, spec: &dyn ChainSpec) -> sc_cli::Result<()> {
+		let storage = spec.build_storage().map_err(sc_cli::Error::Input)?;
+		let code = storage
+			.top
+			.get(well_known_keys::CODE)
+			.ok_or_else(|| sc_cli::Error::Input("The genesis state has no runtime code".into()))?;
+		write(code, self.output.as_ref(), self.raw)
+	}
+}
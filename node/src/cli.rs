@@ -1,3 +1,7 @@
+use crate::{
+	chain_spec, eth::EthConfiguration, export::ExportConfiguration, indexer::IndexerConfiguration,
+	pool::PoolConfiguration, proposer::ProposerConfiguration,
+};
 use sc_cli::RunCmd;
 
 #[derive(Debug, clap::Parser)]
@@ -7,6 +11,21 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	#[command(flatten)]
+	pub eth: EthConfiguration,
+
+	#[command(flatten)]
+	pub indexer: IndexerConfiguration,
+
+	#[command(flatten)]
+	pub export: ExportConfiguration,
+
+	#[command(flatten)]
+	pub pool: PoolConfiguration,
+
+	#[command(flatten)]
+	pub proposer: ProposerConfiguration,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -18,6 +37,9 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Build a chain specification from a declarative config file.
+	GenerateSpec(chain_spec::generator::GenerateSpecCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -27,6 +49,12 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the genesis head of a chain.
+	ExportGenesisState(chain_spec::genesis::ExportGenesisStateCmd),
+
+	/// Export the genesis runtime code of a chain.
+	ExportGenesisWasm(chain_spec::genesis::ExportGenesisWasmCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -2,13 +2,16 @@ use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
 	chain_spec,
 	cli::{Cli, Subcommand},
-	service,
+	indexer,
+	service::{self, FullClient},
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
-use node_template_runtime::{Block, EXISTENTIAL_DEPOSIT};
+use node_template_runtime::{Block, EXISTENTIAL_DEPOSIT, SS58_FORMAT};
 use sc_cli::{ChainSpec, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormat};
 use sp_keyring::Sr25519Keyring;
+use std::sync::Arc;
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
@@ -39,6 +42,8 @@ impl SubstrateCli for Cli {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
+			"npos" => Box::new(chain_spec::npos_testnet_config()?),
+			"bench" => Box::new(chain_spec::bench_config()?),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
 		})
@@ -52,6 +57,8 @@ impl SubstrateCli for Cli {
 /// Parse and run command line arguments
 pub fn run() -> sc_cli::Result<()> {
 	let cli = Cli::from_args();
+	// Addresses are printed, and parsed, in the format of the chain.
+	set_default_ss58_version(Ss58AddressFormat::custom(SS58_FORMAT));
 
 	match &cli.subcommand {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
@@ -59,6 +66,7 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
 		},
+		Some(Subcommand::GenerateSpec(cmd)) => cmd.run(),
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -81,6 +89,14 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportGenesisState(cmd)) => {
+			let params = &cmd.shared_params;
+			cmd.run(&*cli.load_spec(&params.chain_id(params.is_dev()))?)
+		},
+		Some(Subcommand::ExportGenesisWasm(cmd)) => {
+			let params = &cmd.shared_params;
+			cmd.run(&*cli.load_spec(&params.chain_id(params.is_dev()))?)
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -98,7 +114,9 @@ pub fn run() -> sc_cli::Result<()> {
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, backend, .. } =
 					service::new_partial(&config)?;
-				let aux_revert = Box::new(|client, _, blocks| {
+				let revert_indexes = indexer::revert_indexes(&config);
+				let aux_revert = Box::new(move |client: Arc<FullClient>, _, blocks| {
+					revert_indexes(&client, blocks)?;
 					sc_finality_grandpa::revert(client, blocks)?;
 					Ok(())
 				});
@@ -160,7 +178,7 @@ pub fn run() -> sc_cli::Result<()> {
 							Box::new(RemarkBuilder::new(client.clone())),
 							Box::new(TransferKeepAliveBuilder::new(
 								client.clone(),
-								Sr25519Keyring::Alice.to_account_id(),
+								Sr25519Keyring::Alice.to_account_id().into(),
 								EXISTENTIAL_DEPOSIT,
 							)),
 						]);
@@ -204,7 +222,15 @@ pub fn run() -> sc_cli::Result<()> {
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(
+					config,
+					cli.eth.clone(),
+					cli.indexer.clone(),
+					cli.export.clone(),
+					cli.pool.clone(),
+					cli.proposer.clone(),
+				)
+				.map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -0,0 +1,153 @@
+//! Ethereum compatibility for the node: CLI options, the Frontier mapping database and the
+//! background tasks backing the `eth_*` RPC surface.
+
+use std::{
+	collections::BTreeMap,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use fc_mapping_sync::{MappingSyncWorker, SyncStrategy};
+use fc_rpc::{EthTask, OverrideHandle};
+use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
+use futures::{future, prelude::*};
+use node_template_runtime::opaque::Block;
+use sc_client_api::BlockchainEvents;
+use sc_service::{BasePath, Configuration, DatabaseSource, TaskManager};
+use sp_blockchain::HeaderBackend;
+
+use crate::service::{FullBackend, FullClient};
+
+/// The Frontier backend, mapping Ethereum block and transaction hashes to Substrate blocks.
+pub type FrontierBackend = fc_db::Backend<Block>;
+
+/// Options for the `eth_*` RPC surface.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct EthConfiguration {
+	/// Maximum number of logs in a query.
+	#[arg(long, default_value = "10000")]
+	pub max_past_logs: u32,
+
+	/// Maximum fee history cache size.
+	#[arg(long, default_value = "2048")]
+	pub fee_history_limit: u64,
+
+	/// Enable the well-known development accounts for `eth_sign` and `eth_sendTransaction`.
+	#[arg(long)]
+	pub enable_dev_signer: bool,
+
+	/// Maximum allowed gas limit will be `block.gas_limit * execute_gas_limit_multiplier`
+	/// when using `eth_call`/`eth_estimateGas`.
+	#[arg(long, default_value = "10")]
+	pub execute_gas_limit_multiplier: u64,
+
+	/// Size in bytes of the LRU cache for block data.
+	#[arg(long, default_value = "50")]
+	pub eth_log_block_cache: usize,
+
+	/// Size in bytes of the LRU cache for transactions statuses data.
+	#[arg(long, default_value = "50")]
+	pub eth_statuses_cache: usize,
+}
+
+/// Ethereum RPC state shared between the RPC handlers and the background tasks.
+pub struct FrontierPartialComponents {
+	pub filter_pool: Option<FilterPool>,
+	pub fee_history_cache: FeeHistoryCache,
+	pub fee_history_cache_limit: FeeHistoryCacheLimit,
+}
+
+pub fn new_frontier_partial(config: &EthConfiguration) -> FrontierPartialComponents {
+	FrontierPartialComponents {
+		filter_pool: Some(Arc::new(Mutex::new(BTreeMap::new()))),
+		fee_history_cache: Arc::new(Mutex::new(BTreeMap::new())),
+		fee_history_cache_limit: config.fee_history_limit,
+	}
+}
+
+/// The directory node-side databases are kept in, next to the chain database.
+pub fn db_config_dir(config: &Configuration) -> PathBuf {
+	config
+		.base_path
+		.as_ref()
+		.map(|base_path| base_path.config_dir(config.chain_spec.id()))
+		.unwrap_or_else(|| {
+			BasePath::from_project("", "", "node-template").config_dir(config.chain_spec.id())
+		})
+}
+
+fn frontier_database_dir(config: &Configuration, path: &str) -> PathBuf {
+	db_config_dir(config).join("frontier").join(path)
+}
+
+/// Open the Frontier database using the same kind of database as the chain.
+pub fn open_frontier_backend<C>(
+	client: Arc<C>,
+	config: &Configuration,
+) -> Result<Arc<FrontierBackend>, String>
+where
+	C: HeaderBackend<Block>,
+{
+	let source = match config.database {
+		DatabaseSource::RocksDb { .. } =>
+			DatabaseSource::RocksDb { path: frontier_database_dir(config, "db"), cache_size: 0 },
+		DatabaseSource::ParityDb { .. } =>
+			DatabaseSource::ParityDb { path: frontier_database_dir(config, "paritydb") },
+		DatabaseSource::Auto { .. } => DatabaseSource::Auto {
+			rocksdb_path: frontier_database_dir(config, "db"),
+			paritydb_path: frontier_database_dir(config, "paritydb"),
+			cache_size: 0,
+		},
+		_ => return Err("Supported db sources: `rocksdb` | `paritydb` | `auto`".to_string()),
+	};
+
+	Ok(Arc::new(FrontierBackend::new(client, &fc_db::DatabaseSettings { source })?))
+}
+
+/// Spawn the tasks keeping the Frontier database and RPC caches up to date.
+pub fn spawn_frontier_tasks(
+	task_manager: &TaskManager,
+	client: Arc<FullClient>,
+	backend: Arc<FullBackend>,
+	frontier_backend: Arc<FrontierBackend>,
+	filter_pool: Option<FilterPool>,
+	overrides: Arc<OverrideHandle<Block>>,
+	fee_history_cache: FeeHistoryCache,
+	fee_history_cache_limit: FeeHistoryCacheLimit,
+) {
+	// Map Ethereum blocks and receipts to Substrate blocks as they are imported.
+	task_manager.spawn_essential_handle().spawn(
+		"frontier-mapping-sync-worker",
+		Some("frontier"),
+		MappingSyncWorker::new(
+			client.import_notification_stream(),
+			Duration::new(6, 0),
+			client.clone(),
+			backend,
+			frontier_backend,
+			3,
+			0,
+			SyncStrategy::Normal,
+		)
+		.for_each(|()| future::ready(())),
+	);
+
+	// Spawn Frontier EthFilterApi maintenance task.
+	if let Some(filter_pool) = filter_pool {
+		// Each filter is allowed to stay in the pool for 100 blocks.
+		const FILTER_RETAIN_THRESHOLD: u64 = 100;
+		task_manager.spawn_essential_handle().spawn(
+			"frontier-filter-pool",
+			Some("frontier"),
+			EthTask::filter_pool_task(client.clone(), filter_pool, FILTER_RETAIN_THRESHOLD),
+		);
+	}
+
+	// Spawn Frontier FeeHistory cache maintenance task.
+	task_manager.spawn_essential_handle().spawn(
+		"frontier-fee-history",
+		Some("frontier"),
+		EthTask::fee_history_task(client, overrides, fee_history_cache, fee_history_cache_limit),
+	);
+}
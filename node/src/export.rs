@@ -0,0 +1,327 @@
+//! Streaming of the chain to an external sink, as NDJSON records.
+//!
+//! Every block joining the best chain is delivered as an `imported` record, and every finalized
+//! block as a `finalized` record, both with their decoded extrinsics and events. When delivered
+//! blocks leave the best chain, a `retracted` record names them, along with the last delivered
+//! block still in the best chain, before the blocks replacing them are delivered. How far each
+//! stream got is kept in a cursor file, so that a restarted node resumes where it left off. A
+//! fresh node starts streaming from its best and finalized blocks.
+//!
+//! Records are delivered at least once: a record may be delivered again if the node stops
+//! between delivering it and saving the cursor.
+
+pub mod json;
+pub mod sink;
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+
+use codec::{Decode, Encode};
+use futures::{future, stream, StreamExt};
+use node_template_runtime::{BlockNumber, Hash, RuntimeCall, UncheckedExtrinsic};
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sc_service::{Configuration, TaskManager};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::blake2_256;
+use sp_runtime::traits::Header;
+
+use crate::{indexer::block_events, service::FullClient};
+use sink::{Sink, SinkConfig};
+
+/// Options for streaming the chain to an external sink.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportConfiguration {
+	/// Stream imported and finalized blocks to a sink: `file://DIR` for rotating NDJSON files,
+	/// `unix://PATH` for a Unix socket, or an `http://` or `https://` webhook. Webhooks have 30
+	/// seconds to respond to every record before it is sent again.
+	#[arg(long, value_name = "URI")]
+	pub export_sink: Option<SinkConfig>,
+
+	/// Size in bytes at which exported NDJSON files are rotated.
+	#[arg(long, value_name = "BYTES", default_value = "67108864")]
+	pub export_rotate_size: u64,
+}
+
+/// The first delay before delivering a record again.
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// How far the streams got: the last block delivered of each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cursor {
+	/// The last block of the best chain delivered, with its hash.
+	pub imported: Option<(BlockNumber, Hash)>,
+	/// The last finalized block delivered.
+	pub finalized: Option<BlockNumber>,
+}
+
+impl Cursor {
+	/// Load the cursor saved at `path`, if any.
+	pub fn load(path: &Path) -> Result<Option<Self>, String> {
+		match fs::read(path) {
+			Ok(cursor) => serde_json::from_slice(&cursor).map(Some).map_err(|e| e.to_string()),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e.to_string()),
+		}
+	}
+
+	/// Save the cursor at `path`, replacing the previous one at once.
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		let saving = path.with_extension("tmp");
+		let cursor = serde_json::to_vec(self).map_err(|e| e.to_string())?;
+		fs::write(&saving, cursor).map_err(|e| e.to_string())?;
+		fs::rename(&saving, path).map_err(|e| e.to_string())
+	}
+}
+
+/// The record of the block `hash`, at `number`.
+fn record(
+	client: &FullClient,
+	kind: &str,
+	hash: Hash,
+	number: BlockNumber,
+) -> Result<Value, String> {
+	let header = client
+		.header(hash)
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("Missing header of block #{}", number))?;
+	let body = client
+		.block_body(hash)
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("Missing body of block #{}", number))?;
+
+	let mut extrinsics = Vec::new();
+	for (index, extrinsic) in body.iter().enumerate() {
+		let encoded = extrinsic.encode();
+		let extrinsic = UncheckedExtrinsic::decode(&mut &encoded[..])
+			.map_err(|e| format!("Unable to decode extrinsic {}-{}: {}", number, index, e))?;
+		let (signer, nonce) = match &extrinsic.0.signature {
+			Some((address, _, extra)) => (Some(json::to_value(address)), Some(extra.5 .0)),
+			None => (None, None),
+		};
+		let call: &RuntimeCall = &extrinsic.0.function;
+		extrinsics.push(json!({
+			"index": index,
+			"hash": Hash::from(blake2_256(&encoded)),
+			"signer": signer.transpose().map_err(|e| e.to_string())?,
+			"nonce": nonce,
+			"call": json::to_value(call).map_err(|e| e.to_string())?,
+		}));
+	}
+
+	let events = block_events(client, hash, number)?
+		.iter()
+		.map(json::to_value)
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| format!("Unable to decode the events of block #{}: {}", number, e))?;
+
+	Ok(json!({
+		"kind": kind,
+		"number": number,
+		"hash": hash,
+		"parentHash": header.parent_hash(),
+		"extrinsics": extrinsics,
+		"events": events,
+	}))
+}
+
+/// The record of the blocks `retracted` from the best chain, which is left at the block `hash`, at
+/// `number`. Retracted blocks are listed from the highest.
+fn retracted_record(number: BlockNumber, hash: Hash, retracted: &[Hash]) -> Value {
+	json!({
+		"kind": "retracted",
+		"number": number,
+		"hash": hash,
+		"retracted": retracted,
+	})
+}
+
+/// Delivers the records of the chain to a sink, keeping track of the delivered blocks.
+struct Exporter {
+	client: Arc<FullClient>,
+	sink: Sink,
+	cursor_path: PathBuf,
+	cursor: Cursor,
+}
+
+impl Exporter {
+	/// Deliver the record of the block `hash`, at `number`, then move the cursor with `advance`.
+	async fn deliver(
+		&mut self,
+		kind: &str,
+		hash: Hash,
+		number: BlockNumber,
+		advance: impl FnOnce(&mut Cursor),
+	) -> Result<(), String> {
+		let record = record(&self.client, kind, hash, number)?;
+		let line = serde_json::to_vec(&record).map_err(|e| e.to_string())?;
+		self.sink.deliver_with_retry(&line, MIN_BACKOFF).await;
+
+		advance(&mut self.cursor);
+		self.cursor.save(&self.cursor_path)
+	}
+
+	/// Deliver the blocks of the best chain after the cursor. If the delivered blocks left the best
+	/// chain, their retraction is delivered first, and delivery starts again from the last common
+	/// block.
+	async fn sync_imported(&mut self) -> Result<(), String> {
+		let info = self.client.info();
+		let (number, hash) = match self.cursor.imported {
+			Some(imported) => imported,
+			None => {
+				self.cursor.imported = Some((info.best_number, info.best_hash));
+				return self.cursor.save(&self.cursor_path)
+			},
+		};
+
+		let route = sp_blockchain::tree_route(&*self.client, hash, info.best_hash);
+		let (common, retracted) = match route {
+			Ok(route) => (
+				(route.common_block().number, route.common_block().hash),
+				route.retracted().iter().map(|block| block.hash).collect(),
+			),
+			// The delivered blocks left the best chain and were pruned since, which only happens
+			// to blocks older than the finalized ones. Only the last of them is still known.
+			Err(_) => {
+				let common = number.min(info.finalized_number);
+				let common_hash = self
+					.client
+					.hash(common)
+					.map_err(|e| e.to_string())?
+					.ok_or_else(|| format!("Unknown block #{}", common))?;
+				let retracted = if common_hash == hash { Vec::new() } else { vec![hash] };
+				((common, common_hash), retracted)
+			},
+		};
+		if !retracted.is_empty() {
+			let line = serde_json::to_vec(&retracted_record(common.0, common.1, &retracted))
+				.map_err(|e| e.to_string())?;
+			self.sink.deliver_with_retry(&line, MIN_BACKOFF).await;
+
+			self.cursor.imported = Some(common);
+			self.cursor.save(&self.cursor_path)?;
+		}
+
+		let mut next = common.0 + 1;
+		while next <= info.best_number {
+			let hash = self
+				.client
+				.hash(next)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Unknown block #{}", next))?;
+			self.deliver("imported", hash, next, |cursor| cursor.imported = Some((next, hash)))
+				.await?;
+			next += 1;
+		}
+
+		Ok(())
+	}
+
+	/// Deliver the finalized blocks after the cursor.
+	async fn sync_finalized(&mut self) -> Result<(), String> {
+		let finalized = self.client.info().finalized_number;
+		let mut next = match self.cursor.finalized {
+			Some(number) => number + 1,
+			None => {
+				self.cursor.finalized = Some(finalized);
+				return self.cursor.save(&self.cursor_path)
+			},
+		};
+
+		while next <= finalized {
+			let hash = self
+				.client
+				.hash(next)
+				.map_err(|e| e.to_string())?
+				.ok_or_else(|| format!("Unknown block #{}", next))?;
+			self.deliver("finalized", hash, next, |cursor| cursor.finalized = Some(next))
+				.await?;
+			next += 1;
+		}
+
+		Ok(())
+	}
+}
+
+/// Spawn the task streaming the chain to `sink`, rotating files at `rotate_size` bytes.
+pub fn spawn_exporter(
+	task_manager: &TaskManager,
+	config: &Configuration,
+	client: Arc<FullClient>,
+	sink: SinkConfig,
+	rotate_size: u64,
+) -> Result<(), String> {
+	let dir = crate::eth::db_config_dir(config).join("export");
+	fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+	let cursor_path = dir.join("cursor.json");
+	let cursor = Cursor::load(&cursor_path)?.unwrap_or_default();
+	let sink = Sink::new(sink, rotate_size)?;
+
+	let mut exporter = Exporter { client, sink, cursor_path, cursor };
+	task_manager
+		.spawn_handle()
+		.spawn_blocking("block-export", Some("export"), async move {
+			let best_blocks = exporter
+				.client
+				.import_notification_stream()
+				.filter(|block| future::ready(block.is_new_best))
+				.map(|_| ());
+			let finalized_blocks = exporter.client.finality_notification_stream().map(|_| ());
+			let mut notifications = stream::select(best_blocks, finalized_blocks);
+			loop {
+				if let Err(e) = exporter.sync_imported().await {
+					log::warn!(target: "export", "Failed to export imported blocks: {}", e);
+				}
+				if let Err(e) = exporter.sync_finalized().await {
+					log::warn!(target: "export", "Failed to export finalized blocks: {}", e);
+				}
+				if notifications.next().await.is_none() {
+					break
+				}
+			}
+		});
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cursors_survive_restarts() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("cursor.json");
+		assert_eq!(Cursor::load(&path), Ok(None));
+
+		let cursor = Cursor { imported: Some((7, Hash::repeat_byte(7))), finalized: Some(5) };
+		cursor.save(&path).unwrap();
+		assert_eq!(Cursor::load(&path), Ok(Some(cursor)));
+		assert!(!path.with_extension("tmp").exists());
+	}
+
+	#[test]
+	fn retracted_records_name_the_retracted_blocks() {
+		let record = retracted_record(
+			5,
+			Hash::repeat_byte(5),
+			&[Hash::repeat_byte(7), Hash::repeat_byte(6)],
+		);
+
+		assert_eq!(
+			record,
+			json!({
+				"kind": "retracted",
+				"number": 5,
+				"hash": Hash::repeat_byte(5),
+				"retracted": [Hash::repeat_byte(7), Hash::repeat_byte(6)],
+			})
+		);
+	}
+}
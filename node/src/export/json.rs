@@ -0,0 +1,171 @@
+//! Decoding of SCALE encoded values into JSON, following the type information of their type.
+//!
+//! Composites with named fields become objects, other composites and tuples become arrays, or
+//! their only field. Enum values become `{ "Variant": fields }`, or `"Variant"` without fields.
+//! Byte sequences and arrays become hex strings, and accounts SS58 addresses. 128-bit and 256-bit
+//! integers become decimal and hex strings respectively, so that no precision is lost.
+
+use codec::{Compact, Decode, Encode};
+use node_template_runtime::AccountId;
+use scale_info::{form::MetaForm, Field, MetaType, TypeDef, TypeDefPrimitive, TypeInfo};
+use serde_json::{Map, Value};
+use sp_core::crypto::Ss58Codec;
+
+/// The JSON representation of `value`.
+pub fn to_value<T: Encode + TypeInfo + 'static>(value: &T) -> Result<Value, codec::Error> {
+	let encoded = value.encode();
+	let mut input = &encoded[..];
+	let value = decode(MetaType::new::<T>(), &mut input)?;
+	if !input.is_empty() {
+		return Err("Trailing data".into())
+	}
+	Ok(value)
+}
+
+/// Decode the value of type `ty` at the start of `input`, and skip past it.
+pub fn decode(ty: MetaType, input: &mut &[u8]) -> Result<Value, codec::Error> {
+	if ty == MetaType::new::<AccountId>() {
+		return Ok(AccountId::decode(input)?.to_ss58check().into())
+	}
+
+	let value = match ty.type_info().type_def() {
+		TypeDef::Composite(composite) => fields(composite.fields(), input)?,
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input)?;
+			let variant = variant
+				.variants()
+				.iter()
+				.find(|variant| variant.index() == index)
+				.ok_or("Unknown variant")?;
+			if variant.fields().is_empty() {
+				Value::String(variant.name().to_string())
+			} else {
+				let mut object = Map::new();
+				object.insert(variant.name().to_string(), fields(variant.fields(), input)?);
+				Value::Object(object)
+			}
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input)?.0;
+			sequence_of(*sequence.type_param(), len, input)?
+		},
+		TypeDef::Array(array) => sequence_of(*array.type_param(), array.len(), input)?,
+		TypeDef::Tuple(tuple) => Value::Array(
+			tuple.fields().iter().map(|ty| decode(*ty, input)).collect::<Result<_, _>>()?,
+		),
+		TypeDef::Primitive(primitive) => match primitive {
+			TypeDefPrimitive::Bool => bool::decode(input)?.into(),
+			TypeDefPrimitive::Char =>
+				char::from_u32(u32::decode(input)?).ok_or("Invalid char")?.into(),
+			TypeDefPrimitive::Str => String::decode(input)?.into(),
+			TypeDefPrimitive::U8 => u8::decode(input)?.into(),
+			TypeDefPrimitive::U16 => u16::decode(input)?.into(),
+			TypeDefPrimitive::U32 => u32::decode(input)?.into(),
+			TypeDefPrimitive::U64 => u64::decode(input)?.into(),
+			TypeDefPrimitive::U128 => u128::decode(input)?.to_string().into(),
+			TypeDefPrimitive::I8 => i8::decode(input)?.into(),
+			TypeDefPrimitive::I16 => i16::decode(input)?.into(),
+			TypeDefPrimitive::I32 => i32::decode(input)?.into(),
+			TypeDefPrimitive::I64 => i64::decode(input)?.into(),
+			TypeDefPrimitive::I128 => i128::decode(input)?.to_string().into(),
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => hex(take(input, 32)?),
+		},
+		TypeDef::Compact(compact) => {
+			let value = Compact::<u128>::decode(input)?.0;
+			if *compact.type_param() == MetaType::new::<u128>() {
+				value.to_string().into()
+			} else {
+				u64::try_from(value).map_err(|_| "Compact value out of range")?.into()
+			}
+		},
+		TypeDef::BitSequence(_) => return Err("Bit sequences are not supported".into()),
+	};
+
+	Ok(value)
+}
+
+fn fields(fields: &[Field<MetaForm>], input: &mut &[u8]) -> Result<Value, codec::Error> {
+	if let [field] = fields {
+		if field.name().is_none() {
+			return decode(*field.ty(), input)
+		}
+	}
+
+	if !fields.is_empty() && fields.iter().all(|field| field.name().is_some()) {
+		let mut object = Map::new();
+		for field in fields {
+			let name = field.name().expect("All fields are named; qed");
+			object.insert(name.to_string(), decode(*field.ty(), input)?);
+		}
+		Ok(Value::Object(object))
+	} else {
+		let values = fields.iter().map(|field| decode(*field.ty(), input));
+		Ok(Value::Array(values.collect::<Result<_, _>>()?))
+	}
+}
+
+fn sequence_of(ty: MetaType, len: u32, input: &mut &[u8]) -> Result<Value, codec::Error> {
+	if ty == MetaType::new::<u8>() {
+		return Ok(hex(take(input, len as usize)?))
+	}
+
+	let values = (0..len).map(|_| decode(ty, input));
+	Ok(Value::Array(values.collect::<Result<_, _>>()?))
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], codec::Error> {
+	if input.len() < len {
+		return Err("Not enough data".into())
+	}
+	let (taken, rest) = input.split_at(len);
+	*input = rest;
+	Ok(taken)
+}
+
+fn hex(bytes: &[u8]) -> Value {
+	sp_core::bytes::to_hex(bytes, false).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use node_template_runtime::{Address, BalancesCall, RuntimeCall, SystemCall};
+	use serde_json::json;
+	use sp_keyring::AccountKeyring;
+
+	fn decoded<T: Encode + TypeInfo + 'static>(value: T) -> Value {
+		to_value(&value).unwrap()
+	}
+
+	#[test]
+	fn calls_decode_to_named_fields() {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let call = RuntimeCall::Balances(BalancesCall::transfer {
+			dest: Address::Id(alice.clone()),
+			value: 5,
+		});
+
+		assert_eq!(
+			decoded(call),
+			json!({
+				"Balances": {
+					"transfer": { "dest": { "Id": alice.to_ss58check() }, "value": "5" }
+				}
+			}),
+		);
+	}
+
+	#[test]
+	fn bytes_decode_to_hex() {
+		let call = RuntimeCall::System(SystemCall::remark { remark: vec![0xde, 0xad] });
+
+		assert_eq!(decoded(call), json!({ "System": { "remark": { "remark": "0xdead" } } }));
+	}
+
+	#[test]
+	fn unit_variants_decode_to_their_name() {
+		assert_eq!(decoded(Some(())), json!({ "Some": [] }));
+		assert_eq!(decoded(None::<u32>), json!("None"));
+		assert_eq!(decoded((1u32, true)), json!([1, true]));
+	}
+}
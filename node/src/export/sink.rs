@@ -0,0 +1,287 @@
+//! Where exported records are delivered.
+
+use std::{
+	fs::{self, File, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+	str::FromStr,
+	time::Duration,
+};
+
+use hyper::{client::HttpConnector, header, Body, Client, Request, Uri};
+use hyper_rustls::HttpsConnector;
+use tokio::{io::AsyncWriteExt, net::UnixStream};
+
+const FILE_PREFIX: &str = "blocks-";
+const FILE_SUFFIX: &str = ".ndjson";
+/// How long a webhook has to respond to a record before the delivery is retried.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where to deliver exported records, as given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkConfig {
+	/// `file://DIR`: append records to rotating NDJSON files in a directory.
+	Directory(PathBuf),
+	/// `unix://PATH`: write records to a Unix socket.
+	Unix(PathBuf),
+	/// `http://URL` or `https://URL`: `POST` every record to a webhook.
+	Http(Uri),
+}
+
+impl FromStr for SinkConfig {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(dir) = s.strip_prefix("file://") {
+			Ok(Self::Directory(dir.into()))
+		} else if let Some(path) = s.strip_prefix("unix://") {
+			Ok(Self::Unix(path.into()))
+		} else if s.starts_with("http://") || s.starts_with("https://") {
+			s.parse().map(Self::Http).map_err(|e| format!("Invalid webhook URL: {}", e))
+		} else {
+			Err("Expected a file://, unix://, http:// or https:// sink".into())
+		}
+	}
+}
+
+/// A destination for exported records, delivered one line at a time.
+pub enum Sink {
+	/// Rotating NDJSON files.
+	Directory(Rotating),
+	/// A Unix socket, reconnected to after failures.
+	Unix { path: PathBuf, stream: Option<UnixStream> },
+	/// A webhook, given `timeout` to respond.
+	Http { client: Client<HttpsConnector<HttpConnector>>, uri: Uri, timeout: Duration },
+}
+
+impl Sink {
+	/// Prepare delivering to `config`, rotating files once they reach `rotate_size` bytes.
+	pub fn new(config: SinkConfig, rotate_size: u64) -> Result<Self, String> {
+		Ok(match config {
+			SinkConfig::Directory(dir) => Self::Directory(Rotating::open(dir, rotate_size)?),
+			SinkConfig::Unix(path) => Self::Unix { path, stream: None },
+			SinkConfig::Http(uri) => {
+				let connector = hyper_rustls::HttpsConnectorBuilder::new()
+					.with_native_roots()
+					.https_or_http()
+					.enable_http1()
+					.build();
+				let client = Client::builder().build(connector);
+				Self::Http { client, uri, timeout: WEBHOOK_TIMEOUT }
+			},
+		})
+	}
+
+	/// Deliver `line`, a record without its trailing newline, once.
+	pub async fn deliver(&mut self, line: &[u8]) -> Result<(), String> {
+		match self {
+			Self::Directory(files) => files.append(line),
+			Self::Unix { path, stream } => {
+				let mut connected = match stream.take() {
+					Some(connected) => connected,
+					None => UnixStream::connect(path.as_path()).await.map_err(|e| e.to_string())?,
+				};
+				// A stream that failed is dropped, to reconnect on the next attempt.
+				connected.write_all(&[line, b"\n"].concat()).await.map_err(|e| e.to_string())?;
+				*stream = Some(connected);
+				Ok(())
+			},
+			Self::Http { client, uri, timeout } => {
+				let request = Request::post(uri.clone())
+					.header(header::CONTENT_TYPE, "application/x-ndjson")
+					.body(Body::from([line, b"\n"].concat()))
+					.map_err(|e| e.to_string())?;
+				let response = tokio::time::timeout(*timeout, client.request(request))
+					.await
+					.map_err(|_| format!("The webhook didn't respond within {:?}", timeout))?
+					.map_err(|e| e.to_string())?;
+				if response.status().is_success() {
+					Ok(())
+				} else {
+					Err(format!("The webhook responded {}", response.status()))
+				}
+			},
+		}
+	}
+
+	/// Deliver `line`, retrying with exponential backoff from `min_backoff` up to a minute until
+	/// it is delivered.
+	pub async fn deliver_with_retry(&mut self, line: &[u8], min_backoff: Duration) {
+		const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+		let mut backoff = min_backoff;
+		while let Err(e) = self.deliver(line).await {
+			log::warn!(
+				target: "export",
+				"Failed to deliver a record, retrying in {:?}: {}",
+				backoff,
+				e,
+			);
+			tokio::time::sleep(backoff).await;
+			backoff = (backoff * 2).min(MAX_BACKOFF);
+		}
+	}
+}
+
+/// NDJSON files in a directory, named `blocks-<sequence number>.ndjson`. Records are appended to
+/// the last file until it reaches the rotation size, then to a new one.
+pub struct Rotating {
+	dir: PathBuf,
+	rotate_size: u64,
+	sequence: u64,
+	file: File,
+	size: u64,
+}
+
+impl Rotating {
+	/// Open the last file in `dir`, creating the directory if needed.
+	pub fn open(dir: PathBuf, rotate_size: u64) -> Result<Self, String> {
+		fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+		let mut sequence = 0;
+		for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+			let name = entry.map_err(|e| e.to_string())?.file_name();
+			let found = name.to_str().and_then(|name| {
+				name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?.parse().ok()
+			});
+			sequence = sequence.max(found.unwrap_or(0));
+		}
+
+		let (file, size) = open_file(&dir, sequence)?;
+		Ok(Self { dir, rotate_size, sequence, file, size })
+	}
+
+	fn append(&mut self, line: &[u8]) -> Result<(), String> {
+		let line = [line, b"\n"].concat();
+		if self.size > 0 && self.size + line.len() as u64 > self.rotate_size {
+			let (file, size) = open_file(&self.dir, self.sequence + 1)?;
+			self.sequence += 1;
+			self.file = file;
+			self.size = size;
+		}
+
+		self.file.write_all(&line).map_err(|e| e.to_string())?;
+		self.file.sync_data().map_err(|e| e.to_string())?;
+		self.size += line.len() as u64;
+		Ok(())
+	}
+}
+
+fn open_file(dir: &Path, sequence: u64) -> Result<(File, u64), String> {
+	let path = dir.join(format!("{}{:08}{}", FILE_PREFIX, sequence, FILE_SUFFIX));
+	let file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(path)
+		.map_err(|e| e.to_string())?;
+	let size = file.metadata().map_err(|e| e.to_string())?.len();
+	Ok((file, size))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{
+		io::{BufRead, BufReader, Read},
+		net::TcpListener,
+		thread,
+	};
+
+	#[test]
+	fn sinks_parse_from_uris() {
+		assert_eq!("file:///tmp/blocks".parse(), Ok(SinkConfig::Directory("/tmp/blocks".into())));
+		assert_eq!(
+			"unix:///tmp/blocks.sock".parse(),
+			Ok(SinkConfig::Unix("/tmp/blocks.sock".into()))
+		);
+		assert!(matches!("https://example.com/hook".parse(), Ok(SinkConfig::Http(_))));
+		assert!("ftp://example.com".parse::<SinkConfig>().is_err());
+	}
+
+	#[tokio::test]
+	async fn files_rotate_at_the_rotation_size() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = SinkConfig::Directory(dir.path().into());
+		let mut sink = Sink::new(config.clone(), 10).unwrap();
+		for line in ["first", "second", "third"] {
+			sink.deliver(line.as_bytes()).await.unwrap();
+		}
+
+		let read = |sequence: u64| {
+			fs::read_to_string(dir.path().join(format!("blocks-{:08}.ndjson", sequence))).unwrap()
+		};
+		assert_eq!(read(0), "first\n");
+		assert_eq!(read(1), "second\n");
+		assert_eq!(read(2), "third\n");
+
+		// Restarting appends to the last file.
+		let mut sink = Sink::new(config, 100).unwrap();
+		sink.deliver(b"fourth").await.unwrap();
+		assert_eq!(read(2), "third\nfourth\n");
+	}
+
+	/// Answer the requests of `statuses.len()` connections with `statuses`, one per connection,
+	/// returning the bodies received.
+	fn webhook(statuses: Vec<&'static str>) -> (Uri, thread::JoinHandle<Vec<String>>) {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let uri = format!("http://{}/blocks", listener.local_addr().unwrap()).parse().unwrap();
+
+		let handle = thread::spawn(move || {
+			let mut bodies = Vec::new();
+			for status in statuses {
+				let (stream, _) = listener.accept().unwrap();
+				let mut reader = BufReader::new(stream);
+				let mut length = 0;
+				loop {
+					let mut line = String::new();
+					reader.read_line(&mut line).unwrap();
+					if line == "\r\n" {
+						break
+					}
+					if let Some((name, value)) = line.split_once(':') {
+						if name.eq_ignore_ascii_case("content-length") {
+							length = value.trim().parse().unwrap();
+						}
+					}
+				}
+				let mut body = vec![0; length];
+				reader.read_exact(&mut body).unwrap();
+				bodies.push(String::from_utf8(body).unwrap());
+
+				let response = format!(
+					"HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+					status
+				);
+				reader.get_mut().write_all(response.as_bytes()).unwrap();
+			}
+			bodies
+		});
+
+		(uri, handle)
+	}
+
+	#[tokio::test]
+	async fn webhooks_are_retried_until_they_succeed() {
+		let (uri, webhook) =
+			webhook(vec!["503 Service Unavailable", "503 Service Unavailable", "200 OK"]);
+		let mut sink = Sink::new(SinkConfig::Http(uri), 0).unwrap();
+
+		sink.deliver_with_retry(br#"{"number":1}"#, Duration::from_millis(1)).await;
+
+		assert_eq!(webhook.join().unwrap(), vec!["{\"number\":1}\n"; 3]);
+	}
+
+	#[tokio::test]
+	async fn webhooks_that_never_respond_time_out() {
+		// Connections are accepted by the system, but nothing ever reads or answers them.
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let uri = format!("http://{}/blocks", listener.local_addr().unwrap()).parse().unwrap();
+		let mut sink = Sink::new(SinkConfig::Http(uri), 0).unwrap();
+		if let Sink::Http { timeout, .. } = &mut sink {
+			*timeout = Duration::from_millis(100);
+		}
+
+		let delivered = sink.deliver(b"{}").await;
+
+		assert_eq!(delivered, Err("The webhook didn't respond within 100ms".into()));
+	}
+}
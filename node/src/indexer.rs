@@ -0,0 +1,347 @@
+//! Node-side indexes of the chain history, for RPCs needing history the chain state doesn't keep.
+//!
+//! Every index is a ParityDb database of its own, kept in line with the best chain by a
+//! background task. Whatever an index writes for a block is recorded with the block's number and
+//! hash, so that it can be forgotten when the block leaves the best chain.
+//!
+//! Indexing reads the state of every block, so indexes should be enabled from the start, or on an
+//! archive node. An index whose next block's state has already been pruned fails to start.
+
+pub mod accounts;
+pub mod events;
+
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use codec::{Compact, Decode, Encode};
+use frame_system::EventRecord;
+use futures::{future, StreamExt};
+use node_template_runtime::{BlockNumber, Hash, RuntimeEvent};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_service::{Configuration, TaskManager};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+
+use crate::service::FullClient;
+
+/// Options for the node-side indexes.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct IndexerConfiguration {
+	/// Index the events of the best chain, for the `events_query` RPC.
+	#[arg(long)]
+	pub index_events: bool,
+
+	/// Index the extrinsics of the best chain by signer, for the `account_history` RPC.
+	#[arg(long)]
+	pub index_accounts: bool,
+}
+
+/// Column of the highest indexed block number.
+const META: u8 = 0;
+/// Column of the hash of every indexed block, and of the keys written for it.
+const BLOCKS: u8 = 1;
+/// The first column available to indexes.
+pub const FIRST_COLUMN: u8 = 2;
+
+const TIP_KEY: &[u8] = b"tip";
+
+/// What is recorded of an indexed block: its hash, and the keys written for it.
+type IndexedBlock = (Hash, Vec<(u8, Vec<u8>)>);
+
+/// A write to an index database: column, key and value.
+pub type Entry = (u8, Vec<u8>, Vec<u8>);
+
+/// A position in the chain: a block number, and an index in the block, e.g. of an extrinsic.
+pub type Position = (BlockNumber, u32);
+
+/// The key of `position`, ordering keys in chain order. Index keys end with it.
+pub fn position_key((number, index): Position) -> [u8; 8] {
+	let mut key = [0; 8];
+	key[..4].copy_from_slice(&number.to_be_bytes());
+	key[4..].copy_from_slice(&index.to_be_bytes());
+	key
+}
+
+/// The position at the end of the index key `key`.
+pub fn position_of(key: &[u8]) -> Position {
+	let (number, index) = key[key.len() - 8..].split_at(4);
+	(
+		BlockNumber::from_be_bytes(number.try_into().expect("Split at 4 bytes; qed")),
+		u32::from_be_bytes(index.try_into().expect("Split at 4 bytes; qed")),
+	)
+}
+
+/// The events deposited in the block `hash`, at `number`.
+///
+/// Events are decoded with the types of the node's runtime. Should an event not decode with them,
+/// e.g. one deposited before a runtime upgrade, it is skipped along with the events after it, as
+/// there is no telling where it ends.
+pub fn block_events(
+	client: &FullClient,
+	hash: Hash,
+	number: BlockNumber,
+) -> Result<Vec<EventRecord<RuntimeEvent, Hash>>, String> {
+	let key = StorageKey([sp_core::twox_128(b"System"), sp_core::twox_128(b"Events")].concat());
+	let events = match client.storage(hash, &key).map_err(|e| e.to_string())? {
+		Some(events) => events.0,
+		None => return Ok(Vec::new()),
+	};
+
+	let input = &mut &events[..];
+	let mut records = Vec::new();
+	let count = match Compact::<u32>::decode(input) {
+		Ok(count) => count.0,
+		Err(e) => {
+			log::warn!(target: "indexer", "Skipping the events of block #{}: {}", number, e);
+			return Ok(Vec::new())
+		},
+	};
+	for index in 0..count {
+		match EventRecord::decode(input) {
+			Ok(record) => records.push(record),
+			Err(e) => {
+				log::warn!(
+					target: "indexer",
+					"Skipping the events of block #{} from event {}: {}",
+					number,
+					index,
+					e,
+				);
+				break
+			},
+		}
+	}
+
+	Ok(records)
+}
+
+/// Extracts the entries to index from blocks.
+pub trait Index: Send + 'static {
+	/// The name of the index, naming its database.
+	const NAME: &'static str;
+	/// The number of columns the index writes to, from [`FIRST_COLUMN`] on. They are all ordered,
+	/// so they can be iterated from any key.
+	const COLUMNS: u8;
+
+	/// The entries indexing the block `hash`, at `number`. Their keys must be unique to the block.
+	fn entries(
+		&self,
+		client: &FullClient,
+		hash: Hash,
+		number: BlockNumber,
+	) -> Result<Vec<Entry>, String>;
+}
+
+/// The database of an index.
+pub struct IndexDb {
+	db: parity_db::Db,
+}
+
+impl IndexDb {
+	/// Open the database at `path`, for an index using `columns` columns.
+	pub fn open(path: &Path, columns: u8) -> Result<Self, String> {
+		let mut options = parity_db::Options::with_columns(path, FIRST_COLUMN + columns);
+		for column in &mut options.columns[FIRST_COLUMN as usize..] {
+			column.btree_index = true;
+		}
+
+		let db = parity_db::Db::open_or_create(&options).map_err(|e| e.to_string())?;
+		Ok(Self { db })
+	}
+
+	/// The value under `key` in `column`.
+	pub fn get(&self, column: u8, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		self.db.get(column, key).map_err(|e| e.to_string())
+	}
+
+	/// The entries of `column`, in key order, starting from `start`.
+	pub fn iter_from(
+		&self,
+		column: u8,
+		start: &[u8],
+	) -> Result<impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), String>> + '_, String> {
+		let mut iter = self.db.iter(column).map_err(|e| e.to_string())?;
+		iter.seek(start).map_err(|e| e.to_string())?;
+		Ok(std::iter::from_fn(move || iter.next().map_err(|e| e.to_string()).transpose()))
+	}
+
+	/// The highest indexed block number.
+	pub fn tip(&self) -> Result<Option<BlockNumber>, String> {
+		self.decoded(META, TIP_KEY)
+	}
+
+	/// The hash of the block indexed at `number`.
+	pub fn block_hash(&self, number: BlockNumber) -> Result<Option<Hash>, String> {
+		let block: Option<IndexedBlock> = self.decoded(BLOCKS, &number.encode())?;
+		Ok(block.map(|(hash, _)| hash))
+	}
+
+	/// Index `entries` for the block `hash`, at `number`, on top of the indexed blocks.
+	fn insert(&self, number: BlockNumber, hash: Hash, entries: Vec<Entry>) -> Result<(), String> {
+		let keys: Vec<_> = entries.iter().map(|(column, key, _)| (*column, key.clone())).collect();
+		let block = (BLOCKS, number.encode(), Some((hash, keys).encode()));
+		let tip = (META, TIP_KEY.to_vec(), Some(number.encode()));
+
+		let entries = entries.into_iter().map(|(column, key, value)| (column, key, Some(value)));
+		self.db.commit(entries.chain([block, tip])).map_err(|e| e.to_string())
+	}
+
+	/// Forget the blocks indexed after `number`, or all of them if `None`.
+	pub fn revert_to(&self, number: Option<BlockNumber>) -> Result<(), String> {
+		let first_reverted = number.map_or(0, |number| number + 1);
+		let tip = match self.tip()? {
+			Some(tip) if tip >= first_reverted => tip,
+			_ => return Ok(()),
+		};
+
+		let mut removed = Vec::new();
+		for reverted in first_reverted..=tip {
+			let block: Option<IndexedBlock> = self.decoded(BLOCKS, &reverted.encode())?;
+			if let Some((_, keys)) = block {
+				removed.extend(keys.into_iter().map(|(column, key)| (column, key, None)));
+			}
+			removed.push((BLOCKS, reverted.encode(), None));
+		}
+		let tip = (META, TIP_KEY.to_vec(), number.map(|number| number.encode()));
+
+		self.db.commit(removed.into_iter().chain([tip])).map_err(|e| e.to_string())
+	}
+
+	fn decoded<T: Decode>(&self, column: u8, key: &[u8]) -> Result<Option<T>, String> {
+		self.get(column, key)?
+			.map(|value| T::decode(&mut &value[..]).map_err(|e| e.to_string()))
+			.transpose()
+	}
+}
+
+/// Bring `db` in line with the best chain: forget the blocks that left it, and index the ones
+/// that joined it.
+fn sync<I: Index>(client: &FullClient, index: &I, db: &IndexDb) -> Result<(), String> {
+	let mut tip = db.tip()?;
+	while let Some(number) = tip {
+		if db.block_hash(number)? == client.hash(number).map_err(|e| e.to_string())? {
+			break
+		}
+		tip = number.checked_sub(1);
+	}
+	db.revert_to(tip)?;
+
+	let first = tip.map_or(0, |tip| tip + 1);
+	for number in first..=client.info().best_number {
+		let hash = client
+			.hash(number)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Unknown block #{}", number))?;
+		db.insert(number, hash, index.entries(client, hash, number)?)?;
+	}
+
+	Ok(())
+}
+
+/// Check that the state of the next block `db` indexes, the first after its tip, is still
+/// available, as told by `has_state`. An index can't skip blocks, so there is no catching up past
+/// pruned state.
+fn check_start(
+	db: &IndexDb,
+	best: BlockNumber,
+	has_state: impl Fn(BlockNumber) -> bool,
+) -> Result<(), String> {
+	let next = db.tip()?.map_or(0, |tip| tip + 1);
+	if next <= best && !has_state(next) {
+		return Err(format!(
+			"The state of block #{} has been pruned, so the index can't be brought up to date. \
+			Run an archive node (--state-pruning archive) or remove the index database",
+			next,
+		))
+	}
+
+	Ok(())
+}
+
+fn index_path(config: &Configuration, name: &str) -> PathBuf {
+	crate::eth::db_config_dir(config).join("indexes").join(name)
+}
+
+/// Open the database of `index`, and spawn the task keeping it in line with the best chain.
+pub fn spawn_index<I: Index>(
+	task_manager: &TaskManager,
+	config: &Configuration,
+	client: Arc<FullClient>,
+	index: I,
+) -> Result<Arc<IndexDb>, String> {
+	let path = index_path(config, I::NAME);
+	let db = Arc::new(IndexDb::open(&path, I::COLUMNS)?);
+	check_start(
+		&db,
+		client.info().best_number,
+		|number| matches!(client.hash(number), Ok(Some(hash)) if client.state_at(hash).is_ok()),
+	)
+	.map_err(|e| format!("Can't start the {} index at {}: {}", I::NAME, path.display(), e))?;
+
+	let task_db = db.clone();
+	task_manager.spawn_handle().spawn_blocking(I::NAME, Some("indexer"), async move {
+		let mut best_blocks =
+			client.import_notification_stream().filter(|block| future::ready(block.is_new_best));
+		loop {
+			if let Err(e) = sync(&client, &index, &task_db) {
+				log::warn!(target: "indexer", "Failed to update the {} index: {}", I::NAME, e);
+			}
+			if best_blocks.next().await.is_none() {
+				break
+			}
+		}
+	});
+
+	Ok(db)
+}
+
+/// Prepare reverting the indexes of the node along with the chain, for the `revert` subcommand.
+///
+/// The returned function makes the indexes forget the last `blocks` blocks of the client, unless
+/// they are finalized, as the chain itself won't revert those.
+pub fn revert_indexes(
+	config: &Configuration,
+) -> impl FnOnce(&FullClient, BlockNumber) -> Result<(), String> {
+	let indexes = [
+		(index_path(config, events::EventIndex::NAME), events::EventIndex::COLUMNS),
+		(index_path(config, accounts::HistoryIndex::NAME), accounts::HistoryIndex::COLUMNS),
+	];
+
+	move |client, blocks| {
+		let info = client.info();
+		let kept = info.best_number.saturating_sub(blocks).max(info.finalized_number);
+		for (path, columns) in indexes {
+			// Indexes the node never kept have nothing to revert.
+			if path.exists() {
+				IndexDb::open(&path, columns)?.revert_to(Some(kept))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn indexes_do_not_start_past_pruned_state() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = IndexDb::open(dir.path(), 1).unwrap();
+		// Only the state of the last 10 blocks is kept.
+		let has_state = |number| number > 90;
+
+		// A new index starts from genesis, long pruned.
+		assert!(check_start(&db, 100, has_state).unwrap_err().contains("block #0 "));
+
+		db.insert(95, Hash::repeat_byte(95), Vec::new()).unwrap();
+		assert_eq!(check_start(&db, 100, has_state), Ok(()));
+		// Nothing is left to index at the best block.
+		assert_eq!(check_start(&db, 95, |_| false), Ok(()));
+
+		db.revert_to(Some(50)).unwrap();
+		assert!(check_start(&db, 100, has_state).unwrap_err().contains("block #51 "));
+	}
+}
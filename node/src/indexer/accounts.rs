@@ -0,0 +1,198 @@
+//! Index of the extrinsics every account signed in the best chain.
+
+use codec::{Decode, Encode};
+use node_template_runtime::{
+	AccountId, AccountIndex, Address, Balance, BlockNumber, Hash, Index as Nonce, RuntimeEvent,
+	UncheckedExtrinsic,
+};
+use sc_client_api::{BlockBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::blake2_256, storage::StorageKey};
+use sp_runtime::{traits::Header as HeaderT, DispatchError};
+
+use super::{
+	block_events, position_key, position_of, Entry, Index, IndexDb, Position, FIRST_COLUMN,
+};
+use crate::service::FullClient;
+
+/// Extrinsics, by `(signer, block number, index in the block)`.
+const HISTORY: u8 = FIRST_COLUMN;
+
+/// What is indexed of a signed extrinsic.
+#[derive(Clone, Encode, Decode)]
+pub struct SignedExtrinsic {
+	/// The hash of the extrinsic.
+	pub hash: Hash,
+	/// The nonce the signer signed the extrinsic with.
+	pub nonce: Nonce,
+	/// The indices of the pallet of the call, and of the call in the pallet.
+	pub call: (u8, u8),
+	/// Whether the call succeeded.
+	pub result: Result<(), DispatchError>,
+}
+
+/// Indexes the signed extrinsics of every block by signer.
+///
+/// Ethereum transactions are left out: they aren't signed by a Substrate account, and the `eth_*`
+/// RPC methods already find them.
+pub struct HistoryIndex;
+
+impl Index for HistoryIndex {
+	const NAME: &'static str = "accounts";
+	const COLUMNS: u8 = 1;
+
+	fn entries(
+		&self,
+		client: &FullClient,
+		hash: Hash,
+		number: BlockNumber,
+	) -> Result<Vec<Entry>, String> {
+		let extrinsics = client
+			.block_body(hash)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Missing body of block #{}", number))?;
+		let events = block_events(client, hash, number)?;
+		// Account indices are resolved as the block found them, before it could change them.
+		let parent = *client
+			.header(hash)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Missing header of block #{}", number))?
+			.parent_hash();
+
+		let mut entries = Vec::new();
+		for (index, extrinsic) in extrinsics.iter().enumerate() {
+			let encoded = extrinsic.encode();
+			let extrinsic = match UncheckedExtrinsic::decode(&mut &encoded[..]) {
+				Ok(extrinsic) => extrinsic,
+				Err(e) => {
+					log::warn!(
+						target: "indexer",
+						"Skipping extrinsic {}-{}, which doesn't decode: {}",
+						number,
+						index,
+						e,
+					);
+					continue
+				},
+			};
+			let (address, _, extra) = match extrinsic.0.signature {
+				Some(signature) => signature,
+				None => continue,
+			};
+			let signer = match address {
+				Address::Id(signer) => signer,
+				Address::Index(account_index) => match lookup(client, parent, account_index) {
+					Ok(signer) => signer,
+					Err(e) => {
+						log::warn!(
+							target: "indexer",
+							"Skipping extrinsic {}-{}: {}",
+							number,
+							index,
+							e,
+						);
+						continue
+					},
+				},
+				// The runtime doesn't accept other addresses.
+				_ => continue,
+			};
+
+			let index = index as u32;
+			let call = extrinsic.0.function.encode();
+			let result = events
+				.iter()
+				.filter(|record| record.phase == frame_system::Phase::ApplyExtrinsic(index))
+				.find_map(|record| match record.event {
+					RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+						dispatch_error,
+						..
+					}) => Some(dispatch_error),
+					_ => None,
+				})
+				.map_or(Ok(()), Err);
+			let signed = SignedExtrinsic {
+				hash: blake2_256(&encoded).into(),
+				nonce: extra.5 .0,
+				call: (call[0], call[1]),
+				result,
+			};
+
+			entries.push((HISTORY, key_of(&signer, (number, index)), signed.encode()));
+		}
+
+		Ok(entries)
+	}
+}
+
+fn key_of(account: &AccountId, position: Position) -> Vec<u8> {
+	let account: &[u8] = account.as_ref();
+	[account, &position_key(position)].concat()
+}
+
+/// The account `account_index` points to, in the state of the block `hash`.
+pub(crate) fn lookup(
+	client: &FullClient,
+	hash: Hash,
+	account_index: AccountIndex,
+) -> Result<AccountId, String> {
+	let index = account_index.encode();
+	let key = StorageKey(
+		[
+			&sp_core::twox_128(b"Indices")[..],
+			&sp_core::twox_128(b"Accounts"),
+			&sp_core::blake2_128(&index),
+			&index,
+		]
+		.concat(),
+	);
+	let value = client
+		.storage(hash, &key)
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("Unknown account index {}", account_index))?;
+	let (account, _, _) = <(AccountId, Balance, bool)>::decode(&mut &value.0[..])
+		.map_err(|e| format!("Unable to decode account index {}: {}", account_index, e))?;
+
+	Ok(account)
+}
+
+/// An extrinsic in the history of an account.
+pub struct HistoryEntry {
+	/// Where the extrinsic is in the chain.
+	pub position: Position,
+	/// The hash of the block of the extrinsic.
+	pub block_hash: Hash,
+	/// The extrinsic.
+	pub extrinsic: SignedExtrinsic,
+}
+
+/// Up to `limit` extrinsics `account` signed, in chain order from `start`, and where the next one
+/// is, if there are more.
+pub fn history(
+	db: &IndexDb,
+	account: &AccountId,
+	start: Option<Position>,
+	limit: usize,
+) -> Result<(Vec<HistoryEntry>, Option<Position>), String> {
+	let prefix: &[u8] = account.as_ref();
+
+	let mut history = Vec::new();
+	for item in db.iter_from(HISTORY, &key_of(account, start.unwrap_or_default()))? {
+		let (key, value) = item?;
+		if !key.starts_with(prefix) {
+			break
+		}
+		let position = position_of(&key);
+		if history.len() == limit {
+			return Ok((history, Some(position)))
+		}
+
+		let extrinsic = SignedExtrinsic::decode(&mut &value[..]).map_err(|e| e.to_string())?;
+		let block_hash = db
+			.block_hash(position.0)?
+			.ok_or_else(|| format!("Missing hash of block #{}", position.0))?;
+		history.push(HistoryEntry { position, block_hash, extrinsic });
+	}
+
+	Ok((history, None))
+}
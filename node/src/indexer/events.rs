@@ -0,0 +1,219 @@
+//! Index of the events deposited in the best chain.
+//!
+//! Events are indexed by pallet and variant, then by block. They are also indexed by the accounts
+//! they mention, anywhere in their fields, and by their topics.
+
+use codec::{Compact, Decode, Encode};
+use frame_system::EventRecord;
+use node_template_runtime::{AccountId, BlockNumber, Hash, RuntimeEvent};
+use scale_info::{form::MetaForm, MetaType, TypeDef, TypeDefPrimitive, Variant};
+
+use super::{block_events, position_key, position_of, Entry, Index, IndexDb, Position, FIRST_COLUMN};
+use crate::service::FullClient;
+
+/// Events, by `(pallet, variant, block number, index in the block)`.
+const EVENTS: u8 = FIRST_COLUMN;
+/// Events, by `(pallet, variant, account or topic, block number, index in the block)`.
+const SUBJECTS: u8 = FIRST_COLUMN + 1;
+
+/// The position of an event in the chain: the number of its block, and its index in the events
+/// of the block.
+pub type EventId = Position;
+
+/// Indexes the events of every block.
+pub struct EventIndex;
+
+impl Index for EventIndex {
+	const NAME: &'static str = "events";
+	const COLUMNS: u8 = 2;
+
+	fn entries(
+		&self,
+		client: &FullClient,
+		hash: Hash,
+		number: BlockNumber,
+	) -> Result<Vec<Entry>, String> {
+		let mut entries = Vec::new();
+		for (index, record) in block_events(client, hash, number)?.into_iter().enumerate() {
+			let event = record.event.encode();
+			let (pallet, variant) = (event[0], event[1]);
+			let id = (number, index as u32);
+
+			let mut subjects = accounts(&event);
+			subjects.extend(record.topics.iter().map(|topic| topic.0));
+			subjects.sort();
+			subjects.dedup();
+			for subject in subjects {
+				entries.push((SUBJECTS, key_of(pallet, variant, &subject, id), Vec::new()));
+			}
+			entries.push((EVENTS, key_of(pallet, variant, &[], id), record.encode()));
+		}
+
+		Ok(entries)
+	}
+}
+
+fn key_of(pallet: u8, variant: u8, subject: &[u8], id: EventId) -> Vec<u8> {
+	[&[pallet, variant][..], subject, &position_key(id)].concat()
+}
+
+/// The variants of the enum `ty`.
+fn variants(ty: MetaType) -> Vec<Variant<MetaForm>> {
+	match ty.type_info().type_def() {
+		TypeDef::Variant(variant) => variant.variants().to_vec(),
+		_ => Vec::new(),
+	}
+}
+
+/// The indices of the event `variant` of `pallet`, by name.
+pub fn event_indices(pallet: &str, variant: &str) -> Option<(u8, u8)> {
+	let pallet = variants(MetaType::new::<RuntimeEvent>())
+		.into_iter()
+		.find(|candidate| *candidate.name() == pallet)?;
+	let event = *pallet.fields().first()?.ty();
+	let variant = variants(event).into_iter().find(|candidate| *candidate.name() == variant)?;
+
+	Some((pallet.index(), variant.index()))
+}
+
+/// The accounts in the SCALE encoded `event`. Events that don't decode as expected don't mention
+/// any account.
+fn accounts(event: &[u8]) -> Vec<[u8; 32]> {
+	let mut accounts = Vec::new();
+	match collect_accounts(MetaType::new::<RuntimeEvent>(), &mut &event[..], &mut accounts) {
+		Ok(()) => accounts,
+		Err(e) => {
+			log::debug!(target: "indexer", "Unable to find the accounts of an event: {}", e);
+			Vec::new()
+		},
+	}
+}
+
+/// Skip past the value of type `ty` at the start of `input`, collecting the accounts in it.
+fn collect_accounts(
+	ty: MetaType,
+	input: &mut &[u8],
+	accounts: &mut Vec<[u8; 32]>,
+) -> Result<(), codec::Error> {
+	if ty == MetaType::new::<AccountId>() {
+		accounts.push(<[u8; 32]>::decode(input)?);
+		return Ok(())
+	}
+
+	match ty.type_info().type_def() {
+		TypeDef::Composite(composite) =>
+			for field in composite.fields() {
+				collect_accounts(*field.ty(), input, accounts)?;
+			},
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input)?;
+			let variant = variant
+				.variants()
+				.iter()
+				.find(|variant| variant.index() == index)
+				.ok_or("Unknown variant")?;
+			for field in variant.fields() {
+				collect_accounts(*field.ty(), input, accounts)?;
+			}
+		},
+		TypeDef::Sequence(sequence) =>
+			for _ in 0..Compact::<u32>::decode(input)?.0 {
+				collect_accounts(*sequence.type_param(), input, accounts)?;
+			},
+		TypeDef::Array(array) =>
+			for _ in 0..array.len() {
+				collect_accounts(*array.type_param(), input, accounts)?;
+			},
+		TypeDef::Tuple(tuple) =>
+			for ty in tuple.fields() {
+				collect_accounts(*ty, input, accounts)?;
+			},
+		TypeDef::Primitive(primitive) => {
+			let size = match primitive {
+				TypeDefPrimitive::Str => Compact::<u32>::decode(input)?.0 as usize,
+				TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+				TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+				TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+				TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+				TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+				TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+			};
+			let rest: &[u8] = input;
+			*input = rest.get(size..).ok_or("Not enough data")?;
+		},
+		TypeDef::Compact(_) => Compact::<u128>::skip(input)?,
+		TypeDef::BitSequence(_) => return Err("Bit sequences are not supported".into()),
+	}
+
+	Ok(())
+}
+
+/// What to look for in the event index.
+pub struct EventFilter {
+	/// The index of the pallet of the events.
+	pub pallet: u8,
+	/// The index of the variant of the events.
+	pub variant: u8,
+	/// An account the events mention, or one of their topics.
+	pub subject: Option<[u8; 32]>,
+	/// The first block to look in.
+	pub from: BlockNumber,
+	/// The last block to look in.
+	pub to: BlockNumber,
+}
+
+/// An indexed event.
+pub struct IndexedEvent {
+	/// Where the event is in the chain.
+	pub id: EventId,
+	/// The hash of the block of the event.
+	pub block_hash: Hash,
+	/// The event, as deposited.
+	pub record: EventRecord<RuntimeEvent, Hash>,
+}
+
+/// Up to `limit` events matching `filter`, in chain order from `start`, and where the next
+/// matching event is, if there are more.
+pub fn query(
+	db: &IndexDb,
+	filter: &EventFilter,
+	start: Option<EventId>,
+	limit: usize,
+) -> Result<(Vec<IndexedEvent>, Option<EventId>), String> {
+	let EventFilter { pallet, variant, subject, from, to } = *filter;
+	let (column, prefix) = match subject {
+		Some(subject) => (SUBJECTS, [&[pallet, variant][..], &subject].concat()),
+		None => (EVENTS, vec![pallet, variant]),
+	};
+	let start = start.map_or((from, 0), |start| start.max((from, 0)));
+
+	let mut events = Vec::new();
+	for item in db.iter_from(column, &[&prefix[..], &position_key(start)].concat())? {
+		let (key, _) = item?;
+		if !key.starts_with(&prefix) {
+			break
+		}
+		let id = position_of(&key);
+		if id.0 > to {
+			break
+		}
+		if events.len() == limit {
+			return Ok((events, Some(id)))
+		}
+
+		events.push(load(db, pallet, variant, id)?);
+	}
+
+	Ok((events, None))
+}
+
+fn load(db: &IndexDb, pallet: u8, variant: u8, id: EventId) -> Result<IndexedEvent, String> {
+	let record = db
+		.get(EVENTS, &key_of(pallet, variant, &[], id))?
+		.ok_or_else(|| format!("Missing event {:?}", id))?;
+	let record = Decode::decode(&mut &record[..]).map_err(|e| e.to_string())?;
+	let block_hash =
+		db.block_hash(id.0)?.ok_or_else(|| format!("Missing hash of block #{}", id.0))?;
+
+	Ok(IndexedEvent { id, block_hash, record })
+}
@@ -1,3 +1,9 @@
+pub mod authoring;
 pub mod chain_spec;
+pub mod eth;
+pub mod export;
+pub mod indexer;
+pub mod pool;
+pub mod proposer;
 pub mod rpc;
 pub mod service;
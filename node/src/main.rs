@@ -4,9 +4,15 @@
 mod chain_spec;
 #[macro_use]
 mod service;
+mod authoring;
 mod benchmarking;
 mod cli;
 mod command;
+mod eth;
+mod export;
+mod indexer;
+mod pool;
+mod proposer;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
@@ -0,0 +1,188 @@
+//! Node-side extensions of the transaction pool.
+//!
+//! The pool can check submitted transactions against node-local admission rules, see
+//! [`admission`].
+//!
+//! With `--pool-snapshot`, the ready and future transactions of the pool are saved to disk
+//! periodically and on shutdown, and imported again on startup. Imported transactions are
+//! validated again against the best block, so the ones included or invalidated meanwhile are
+//! dropped, and checked again against the admission policy, which may have changed meanwhile.
+
+pub mod admission;
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+
+use codec::{Decode, Encode};
+use node_template_runtime::{opaque::Block, AccountId, Address, Hash};
+use sc_service::{Configuration, TaskManager};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TransactionSource};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use crate::{indexer::accounts::lookup, service::FullClient};
+
+/// Options for the transaction pool.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct PoolConfiguration {
+	/// Save the pending transactions of the pool on shutdown and periodically, and import them
+	/// again on startup.
+	#[arg(long)]
+	pub pool_snapshot: bool,
+
+	/// Seconds between two snapshots of the transaction pool.
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		default_value = "60",
+		value_parser = clap::value_parser!(u64).range(1..),
+	)]
+	pub pool_snapshot_interval: u64,
+
+	/// JSON file of admission rules for the transactions submitted to the pool.
+	#[arg(long, value_name = "PATH")]
+	pub pool_admission_policy: Option<PathBuf>,
+}
+
+type Extrinsic = <Block as BlockT>::Extrinsic;
+
+/// The account `address` points to, in the state of the block `hash`.
+pub(crate) fn sender(client: &FullClient, hash: Hash, address: &Address) -> Option<AccountId> {
+	match address {
+		Address::Id(sender) => Some(sender.clone()),
+		Address::Index(index) => lookup(client, hash, *index).ok(),
+		// The runtime doesn't accept other addresses.
+		_ => None,
+	}
+}
+
+fn snapshot_path(config: &Configuration) -> PathBuf {
+	crate::eth::db_config_dir(config).join("txpool").join("snapshot")
+}
+
+/// Save `transactions` at `path`, replacing the previous snapshot at once.
+pub fn write_snapshot(path: &Path, transactions: &[Extrinsic]) -> Result<(), String> {
+	let saving = path.with_extension("tmp");
+	fs::write(&saving, transactions.encode()).map_err(|e| e.to_string())?;
+	fs::rename(&saving, path).map_err(|e| e.to_string())
+}
+
+/// The transactions saved at `path`, if any.
+pub fn read_snapshot(path: &Path) -> Result<Vec<Extrinsic>, String> {
+	match fs::read(path) {
+		Ok(snapshot) => Decode::decode(&mut &snapshot[..]).map_err(|e| e.to_string()),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+		Err(e) => Err(e.to_string()),
+	}
+}
+
+/// Saves the pending transactions of a pool, and a last time when dropped with the task owning it
+/// on shutdown.
+struct Snapshotter<P: TransactionPool<Block = Block>> {
+	pool: Arc<P>,
+	path: PathBuf,
+}
+
+impl<P: TransactionPool<Block = Block>> Snapshotter<P> {
+	fn save(&self) {
+		let transactions: Vec<_> = self
+			.pool
+			.ready()
+			.map(|transaction| transaction.data().clone())
+			.chain(self.pool.futures().into_iter().map(|transaction| transaction.data().clone()))
+			.collect();
+
+		match write_snapshot(&self.path, &transactions) {
+			Ok(()) =>
+				log::debug!(target: "txpool", "Saved {} pool transactions", transactions.len()),
+			Err(e) => log::warn!(target: "txpool", "Failed to save the pool transactions: {}", e),
+		}
+	}
+}
+
+impl<P: TransactionPool<Block = Block>> Drop for Snapshotter<P> {
+	fn drop(&mut self) {
+		self.save();
+	}
+}
+
+/// Submit the transactions of a snapshot to `pool`, validating them against the best block.
+///
+/// They are submitted as external transactions, so that a pool enforcing an admission policy
+/// checks them like the transactions of peers.
+async fn import_snapshot<P>(client: &FullClient, pool: &P, transactions: Vec<Extrinsic>)
+where
+	P: TransactionPool<Block = Block>,
+{
+	let count = transactions.len();
+	let at = BlockId::hash(client.info().best_hash);
+	match pool.submit_at(&at, TransactionSource::External, transactions).await {
+		Ok(results) => log::info!(
+			target: "txpool",
+			"Imported {} of the {} transactions of the pool snapshot",
+			results.iter().filter(|result| result.is_ok()).count(),
+			count,
+		),
+		Err(e) => log::warn!(target: "txpool", "Failed to import the pool snapshot: {}", e),
+	}
+}
+
+/// Import the transactions of the last snapshot into `pool`, and spawn the task saving the pending
+/// transactions every `interval`.
+pub fn spawn_pool_snapshots<P>(
+	task_manager: &TaskManager,
+	config: &Configuration,
+	client: Arc<FullClient>,
+	pool: Arc<P>,
+	interval: Duration,
+) -> Result<(), String>
+where
+	P: TransactionPool<Block = Block> + 'static,
+{
+	let path = snapshot_path(config);
+	fs::create_dir_all(path.parent().expect("The snapshot is in a directory; qed"))
+		.map_err(|e| e.to_string())?;
+	let transactions = read_snapshot(&path)?;
+
+	task_manager
+		.spawn_handle()
+		.spawn("txpool-snapshot", Some("txpool"), async move {
+			if !transactions.is_empty() {
+				import_snapshot(&client, &*pool, transactions).await;
+			}
+
+			let snapshotter = Snapshotter { pool, path };
+			let mut interval = tokio::time::interval(interval);
+			// The first tick completes at once.
+			interval.tick().await;
+			loop {
+				interval.tick().await;
+				snapshotter.save();
+			}
+		});
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snapshots_round_trip() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("snapshot");
+		assert_eq!(read_snapshot(&path), Ok(Vec::new()));
+
+		let transactions = vec![
+			Extrinsic::from_bytes(&[4, 1]).unwrap(),
+			Extrinsic::from_bytes(&[8, 2, 3]).unwrap(),
+		];
+		write_snapshot(&path, &transactions).unwrap();
+		assert_eq!(read_snapshot(&path), Ok(transactions));
+	}
+}
@@ -0,0 +1,627 @@
+//! Node-local admission rules for the transactions submitted to the pool.
+//!
+//! The rules are loaded from a JSON file given with `--pool-admission-policy`, e.g.
+//!
+//! ```json
+//! {
+//!   "minTip": 1000,
+//!   "bannedSenders": ["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"],
+//!   "allowedCalls": [{ "pallet": 5 }, { "pallet": 0, "call": 7 }],
+//!   "blockedCalls": [{ "pallet": 5, "call": 3 }],
+//!   "rateLimit": { "transactions": 10, "periodSecs": 60 }
+//! }
+//! ```
+//!
+//! Pallets and calls are given by their indices, as in `construct_runtime!` and in the pallets.
+//! Call filters apply to the calls nested in batches, proxies, multisigs, sudo and tasks too, which
+//! all need to pass them. Only the transactions the pool accepts count towards the rate limit of
+//! their sender. Every rule is optional. Only transactions from external sources, i.e. submitted
+//! to the `author_*` RPC methods or gossiped by peers, are checked, before they reach the pool.
+//! Rejections are counted in the `txpool_admission_rejections` Prometheus metric, by reason.
+//!
+//! Ethereum transactions sent to `eth_sendRawTransaction` and `eth_sendTransaction` are
+//! submitted from the local source, like the transactions the node submits itself, so the policy
+//! doesn't apply to them until peers gossip them to other nodes.
+
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	future::Future,
+	path::Path,
+	pin::Pin,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use codec::{Compact, Decode, DecodeLimit, Encode};
+use node_template_runtime::{opaque::Block, AccountId, Balance, RuntimeCall, UncheckedExtrinsic};
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
+	PoolFuture, PoolStatus, ReadyTransactions, TransactionFor, TransactionPool, TransactionSource,
+	TransactionStatusStreamFor, TxHash,
+};
+use serde::Deserialize;
+use sp_api::MAX_EXTRINSIC_DEPTH;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+	transaction_validity::InvalidTransaction,
+};
+
+use super::sender;
+use crate::service::FullClient;
+
+/// Calls of a pallet, or one call of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallFilter {
+	/// The index of the pallet.
+	pub pallet: u8,
+	/// The index of the call in the pallet, or `None` for all of them.
+	pub call: Option<u8>,
+}
+
+impl CallFilter {
+	fn matches(&self, (pallet, call): (u8, u8)) -> bool {
+		self.pallet == pallet && self.call.map_or(true, |filtered| filtered == call)
+	}
+}
+
+/// The number of transactions a sender may submit in a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RateLimit {
+	/// The number of transactions.
+	pub transactions: u32,
+	/// The length of the period, in seconds.
+	pub period_secs: u64,
+}
+
+/// The admission rules of the pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AdmissionPolicy {
+	/// The minimum tip of signed transactions.
+	#[serde(default)]
+	pub min_tip: Balance,
+	/// Senders whose transactions are rejected.
+	#[serde(default)]
+	pub banned_senders: HashSet<AccountId>,
+	/// The only calls admitted, if set.
+	pub allowed_calls: Option<Vec<CallFilter>>,
+	/// Calls rejected, even if allowed above.
+	#[serde(default)]
+	pub blocked_calls: Vec<CallFilter>,
+	/// How many transactions every sender may submit.
+	pub rate_limit: Option<RateLimit>,
+}
+
+impl AdmissionPolicy {
+	/// Load the policy in the JSON file at `path`.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let policy = std::fs::read(path).map_err(|e| e.to_string())?;
+		serde_json::from_slice(&policy)
+			.map_err(|e| format!("Invalid admission policy {}: {}", path.display(), e))
+	}
+
+	/// Check a transaction making `calls`, and if signed, its sender and tip. Rate limits are
+	/// left to [`RateLimiter`].
+	pub fn check(
+		&self,
+		calls: &[(u8, u8)],
+		signed: Option<(&AccountId, Balance)>,
+	) -> Result<(), Rejection> {
+		if let Some((sender, tip)) = signed {
+			if self.banned_senders.contains(sender) {
+				return Err(Rejection::BannedSender)
+			}
+			if tip < self.min_tip {
+				return Err(Rejection::TipTooLow)
+			}
+		}
+
+		for &call in calls {
+			let allowed = self
+				.allowed_calls
+				.as_ref()
+				.map_or(true, |allowed| allowed.iter().any(|filter| filter.matches(call)));
+			if !allowed || self.blocked_calls.iter().any(|filter| filter.matches(call)) {
+				return Err(Rejection::CallNotAllowed)
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// The pallet and call indices of `call`, and of the calls nested in it to be dispatched on behalf
+/// of the caller.
+pub fn nested_calls(call: &RuntimeCall) -> Vec<(u8, u8)> {
+	let mut indices = Vec::new();
+	let mut pending = vec![call];
+	while let Some(call) = pending.pop() {
+		let encoded = call.encode();
+		indices.push((encoded[0], encoded[1]));
+
+		match call {
+			RuntimeCall::Utility(
+				pallet_utility::Call::batch { calls } |
+				pallet_utility::Call::batch_all { calls } |
+				pallet_utility::Call::force_batch { calls },
+			) => pending.extend(calls),
+			RuntimeCall::Utility(
+				pallet_utility::Call::as_derivative { call, .. } |
+				pallet_utility::Call::dispatch_as { call, .. } |
+				pallet_utility::Call::with_weight { call, .. },
+			) |
+			RuntimeCall::Proxy(
+				pallet_proxy::Call::proxy { call, .. } |
+				pallet_proxy::Call::proxy_announced { call, .. },
+			) |
+			RuntimeCall::Multisig(
+				pallet_multisig::Call::as_multi { call, .. } |
+				pallet_multisig::Call::as_multi_threshold_1 { call, .. },
+			) |
+			RuntimeCall::Sudo(
+				pallet_sudo::Call::sudo { call } |
+				pallet_sudo::Call::sudo_unchecked_weight { call, .. } |
+				pallet_sudo::Call::sudo_as { call, .. },
+			) |
+			RuntimeCall::Tasks(
+				pallet_tasks::Call::schedule { call, .. } | pallet_tasks::Call::run { call, .. },
+			) => pending.push(&**call),
+			_ => {},
+		}
+	}
+	indices
+}
+
+/// Why a transaction was not admitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+	/// The sender is banned.
+	BannedSender,
+	/// The tip is below the minimum.
+	TipTooLow,
+	/// The call is not allowed.
+	CallNotAllowed,
+	/// The sender submitted too many transactions lately.
+	RateLimited,
+}
+
+impl Rejection {
+	/// The label of the rejection in metrics.
+	fn label(&self) -> &'static str {
+		match self {
+			Self::BannedSender => "banned_sender",
+			Self::TipTooLow => "tip_too_low",
+			Self::CallNotAllowed => "call_not_allowed",
+			Self::RateLimited => "rate_limited",
+		}
+	}
+
+	/// The pool error reported to the submitter.
+	fn into_error(self) -> TxPoolError {
+		match self {
+			Self::BannedSender => TxPoolError::InvalidTransaction(InvalidTransaction::BadSigner),
+			Self::TipTooLow => TxPoolError::InvalidTransaction(InvalidTransaction::Payment),
+			Self::CallNotAllowed => TxPoolError::InvalidTransaction(InvalidTransaction::Call),
+			Self::RateLimited => TxPoolError::TemporarilyBanned,
+		}
+	}
+}
+
+/// Counts the transactions of every sender over a sliding period.
+pub struct RateLimiter {
+	limit: RateLimit,
+	submitted: Mutex<HashMap<AccountId, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+	/// The number of senders above which the senders without recent transactions are forgotten.
+	const PRUNE_ABOVE: usize = 10_000;
+
+	/// Create a new `RateLimiter` enforcing `limit`.
+	pub fn new(limit: RateLimit) -> Self {
+		Self { limit, submitted: Default::default() }
+	}
+
+	/// Whether `sender` may submit another transaction at `now`, without counting it.
+	pub fn allows(&self, sender: &AccountId, now: Instant) -> bool {
+		self.under_limit(sender, now, false)
+	}
+
+	/// Count a transaction of `sender` at `now`, unless `sender` reached the limit.
+	pub fn admit(&self, sender: &AccountId, now: Instant) -> bool {
+		self.under_limit(sender, now, true)
+	}
+
+	/// Count the transaction of `sender` the pool answered with `result`, if it was accepted.
+	fn count_accepted<T, E>(&self, sender: &AccountId, result: &Result<T, E>) {
+		if result.is_ok() {
+			self.admit(sender, Instant::now());
+		}
+	}
+
+	fn under_limit(&self, sender: &AccountId, now: Instant, record: bool) -> bool {
+		let period = Duration::from_secs(self.limit.period_secs);
+		let recent = |submitted: &Instant| now.saturating_duration_since(*submitted) < period;
+
+		let mut submitted = self.submitted.lock().expect("Never poisoned; qed");
+		if submitted.len() > Self::PRUNE_ABOVE {
+			submitted.retain(|_, times| times.back().map_or(false, recent));
+		}
+		let times = submitted.entry(sender.clone()).or_default();
+		while times.front().map_or(false, |time| !recent(time)) {
+			times.pop_front();
+		}
+		if times.len() >= self.limit.transactions as usize {
+			return false
+		}
+		if record {
+			times.push_back(now);
+		}
+		true
+	}
+}
+
+struct Metrics {
+	rejections: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			rejections: register(
+				CounterVec::new(
+					Opts::new(
+						"txpool_admission_rejections",
+						"Transactions rejected by the local admission policy",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// A transaction pool checking the transactions submitted from external sources against an
+/// [`AdmissionPolicy`] before passing them on to the inner pool.
+pub struct AdmissionPool<P> {
+	inner: Arc<P>,
+	client: Arc<FullClient>,
+	policy: AdmissionPolicy,
+	rate_limiter: Option<Arc<RateLimiter>>,
+	metrics: Option<Metrics>,
+}
+
+impl<P> AdmissionPool<P> {
+	/// Create a new `AdmissionPool` enforcing `policy` in front of `inner`, reporting rejections
+	/// to `registry`.
+	pub fn new(
+		inner: Arc<P>,
+		client: Arc<FullClient>,
+		policy: AdmissionPolicy,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		let rate_limiter = policy.rate_limit.map(|limit| Arc::new(RateLimiter::new(limit)));
+		let metrics = registry.map(Metrics::register).transpose()?;
+		Ok(Self { inner, client, policy, rate_limiter, metrics })
+	}
+
+	/// Check `xt`, if it comes from `source`. Transactions that don't decode, or nest calls
+	/// deeper than the runtime accepts, are left to the runtime to reject.
+	///
+	/// Admitted transactions come with the sender to count them against, once the inner pool
+	/// accepts them, if senders are rate limited.
+	fn admit(
+		&self,
+		source: TransactionSource,
+		xt: &<Block as BlockT>::Extrinsic,
+	) -> Result<Option<AccountId>, Rejection> {
+		if source != TransactionSource::External {
+			return Ok(None)
+		}
+		let encoded = xt.encode();
+		let extrinsic = match UncheckedExtrinsic::decode_with_depth_limit(
+			MAX_EXTRINSIC_DEPTH,
+			&encoded[..],
+		) {
+			Ok(extrinsic) => extrinsic,
+			Err(_) => return Ok(None),
+		};
+
+		let signed = match &extrinsic.0.signature {
+			Some((address, _, extra)) => {
+				let sender = sender(&self.client, self.client.info().best_hash, address);
+				let tip =
+					Compact::<Balance>::decode(&mut &extra.7.encode()[..]).map_or(0, |tip| tip.0);
+				sender.map(|sender| (sender, tip))
+			},
+			None => None,
+		};
+
+		let signer = signed.as_ref().map(|(sender, tip)| (sender, *tip));
+		let mut result = self.policy.check(&nested_calls(&extrinsic.0.function), signer);
+		if let (Ok(()), Some(rate_limiter), Some((sender, _))) =
+			(result, &self.rate_limiter, &signed)
+		{
+			if !rate_limiter.allows(sender, Instant::now()) {
+				result = Err(Rejection::RateLimited);
+			}
+		}
+
+		if let Err(rejection) = result {
+			log::debug!(target: "txpool", "Rejected a transaction: {:?}", rejection);
+			if let Some(metrics) = &self.metrics {
+				metrics.rejections.with_label_values(&[rejection.label()]).inc();
+			}
+		}
+		result?;
+		Ok(signed.filter(|_| self.rate_limiter.is_some()).map(|(sender, _)| sender))
+	}
+
+	/// Count the transaction of `sender` against the rate limit once `submitted` shows that the
+	/// inner pool accepted it, so that transactions it rejects, e.g. badly signed ones, don't use
+	/// up the quota of the sender they claim.
+	fn counted<T: 'static, E: 'static>(
+		&self,
+		sender: Option<AccountId>,
+		submitted: PoolFuture<T, E>,
+	) -> PoolFuture<T, E> {
+		let (rate_limiter, sender) = match (&self.rate_limiter, sender) {
+			(Some(rate_limiter), Some(sender)) => (rate_limiter.clone(), sender),
+			_ => return submitted,
+		};
+
+		Box::pin(async move {
+			let result = submitted.await;
+			rate_limiter.count_accepted(&sender, &result);
+			result
+		})
+	}
+}
+
+impl<P> TransactionPool for AdmissionPool<P>
+where
+	P: TransactionPool<Block = Block>,
+{
+	type Block = P::Block;
+	type Hash = P::Hash;
+	type InPoolTransaction = P::InPoolTransaction;
+	type Error = P::Error;
+
+	fn submit_at(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		let mut admitted = Vec::new();
+		let checked: Vec<_> = xts
+			.into_iter()
+			.map(|xt| {
+				self.admit(source, &xt).map(|sender| {
+					admitted.push(xt);
+					sender
+				})
+			})
+			.collect();
+		let submitted = self.inner.submit_at(at, source, admitted);
+		let rate_limiter = self.rate_limiter.clone();
+
+		Box::pin(async move {
+			let mut submitted = submitted.await?.into_iter();
+			Ok(checked
+				.into_iter()
+				.map(|checked| match checked {
+					Ok(sender) => {
+						let result =
+							submitted.next().expect("One result per admitted transaction; qed");
+						if let (Some(rate_limiter), Some(sender)) = (&rate_limiter, sender) {
+							rate_limiter.count_accepted(&sender, &result);
+						}
+						result
+					},
+					Err(rejection) => Err(rejection.into_error().into()),
+				})
+				.collect())
+		})
+	}
+
+	fn submit_one(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		match self.admit(source, &xt) {
+			Ok(sender) => self.counted(sender, self.inner.submit_one(at, source, xt)),
+			Err(rejection) => Box::pin(futures::future::err(rejection.into_error().into())),
+		}
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		match self.admit(source, &xt) {
+			Ok(sender) => self.counted(sender, self.inner.submit_and_watch(at, source, xt)),
+			Err(rejection) => Box::pin(futures::future::err(rejection.into_error().into())),
+		}
+	}
+
+	fn ready_at(
+		&self,
+		at: NumberFor<Self::Block>,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		self.inner.ready_at(at)
+	}
+
+	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
+		self.inner.ready()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.inner.remove_invalid(hashes)
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.inner.status()
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		self.inner.import_notification_stream()
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		self.inner.on_broadcasted(propagations)
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.inner.hash_of(xt)
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.inner.ready_transaction(hash)
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		self.inner.futures()
+	}
+}
+
+#[async_trait::async_trait]
+impl<P> MaintainedTransactionPool for AdmissionPool<P>
+where
+	P: MaintainedTransactionPool<Block = Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		self.inner.maintain(event).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+
+	fn policy(json: &str) -> AdmissionPolicy {
+		serde_json::from_str(json).unwrap()
+	}
+
+	#[test]
+	fn empty_policies_admit_everything() {
+		let policy = policy("{}");
+		let alice = AccountKeyring::Alice.to_account_id();
+
+		assert_eq!(policy, AdmissionPolicy::default());
+		assert_eq!(policy.check(&[(5, 0)], Some((&alice, 0))), Ok(()));
+		assert_eq!(policy.check(&[(0, 7)], None), Ok(()));
+	}
+
+	#[test]
+	fn senders_and_tips_are_checked() {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		let policy = policy(&format!(r#"{{ "minTip": 10, "bannedSenders": ["{}"] }}"#, bob));
+
+		assert_eq!(policy.check(&[(5, 0)], Some((&alice, 10))), Ok(()));
+		assert_eq!(policy.check(&[(5, 0)], Some((&alice, 9))), Err(Rejection::TipTooLow));
+		assert_eq!(policy.check(&[(5, 0)], Some((&bob, 10))), Err(Rejection::BannedSender));
+		// Unsigned transactions have neither.
+		assert_eq!(policy.check(&[(5, 0)], None), Ok(()));
+	}
+
+	#[test]
+	fn calls_are_filtered() {
+		let policy = policy(
+			r#"{
+				"allowedCalls": [{ "pallet": 5 }, { "pallet": 0, "call": 7 }],
+				"blockedCalls": [{ "pallet": 5, "call": 3 }]
+			}"#,
+		);
+
+		assert_eq!(policy.check(&[(5, 0)], None), Ok(()));
+		assert_eq!(policy.check(&[(0, 7)], None), Ok(()));
+		assert_eq!(policy.check(&[(0, 0)], None), Err(Rejection::CallNotAllowed));
+		assert_eq!(policy.check(&[(5, 3)], None), Err(Rejection::CallNotAllowed));
+	}
+
+	#[test]
+	fn senders_are_rate_limited_over_a_sliding_period() {
+		let limiter = RateLimiter::new(RateLimit { transactions: 2, period_secs: 10 });
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		let start = Instant::now();
+		let at = |secs| start + Duration::from_secs(secs);
+
+		assert!(limiter.admit(&alice, at(0)));
+		assert!(limiter.admit(&alice, at(5)));
+		assert!(!limiter.admit(&alice, at(9)));
+		assert!(limiter.admit(&bob, at(9)));
+		// The first transaction left the period.
+		assert!(limiter.admit(&alice, at(10)));
+		assert!(!limiter.admit(&alice, at(14)));
+	}
+
+	#[test]
+	fn nested_calls_are_filtered() {
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: Vec::new() });
+		let remark_with_event =
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: Vec::new() });
+		let blocked = remark.encode();
+		let policy = policy(&format!(
+			r#"{{ "blockedCalls": [{{ "pallet": {}, "call": {} }}] }}"#,
+			blocked[0], blocked[1],
+		));
+		let batch = |calls| RuntimeCall::Utility(pallet_utility::Call::batch { calls });
+		let proxy = |call| {
+			RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+				real: AccountKeyring::Alice.to_account_id().into(),
+				force_proxy_type: None,
+				call: Box::new(call),
+			})
+		};
+
+		let allowed = batch(vec![remark_with_event.clone(), proxy(remark_with_event.clone())]);
+		assert_eq!(policy.check(&nested_calls(&allowed), None), Ok(()));
+		assert_eq!(
+			policy.check(&nested_calls(&batch(vec![remark_with_event, remark.clone()])), None),
+			Err(Rejection::CallNotAllowed)
+		);
+		assert_eq!(
+			policy.check(&nested_calls(&proxy(remark.clone())), None),
+			Err(Rejection::CallNotAllowed)
+		);
+		assert_eq!(
+			policy.check(&nested_calls(&batch(vec![proxy(remark)])), None),
+			Err(Rejection::CallNotAllowed)
+		);
+	}
+
+	#[test]
+	fn only_accepted_transactions_count_towards_the_rate_limit() {
+		let limiter = RateLimiter::new(RateLimit { transactions: 1, period_secs: 10 });
+		let alice = AccountKeyring::Alice.to_account_id();
+
+		// The pool rejects a transaction claiming to be Alice's, with a bad signature.
+		let rejected: Result<(), TxPoolError> =
+			Err(TxPoolError::InvalidTransaction(InvalidTransaction::BadProof));
+		limiter.count_accepted(&alice, &rejected);
+		assert!(limiter.allows(&alice, Instant::now()));
+
+		limiter.count_accepted(&alice, &Ok::<_, TxPoolError>(()));
+		assert!(!limiter.allows(&alice, Instant::now()));
+	}
+}
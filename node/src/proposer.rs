@@ -0,0 +1,607 @@
+//! Block building: which ready transactions are offered to the block builder, in which order, and
+//! for how long.
+//!
+//! The default [`BlockPolicy`] fills blocks up to a share of the size and weight the runtime
+//! allows normal transactions, caps the transactions of every sender in a block, and offers the
+//! transactions of priority calls first, e.g. with `--priority-call TemplateModule`. Priority
+//! transactions never move ahead of the transactions they depend on. Skipped transactions are
+//! counted in the `proposer_skipped_transactions` Prometheus metric, by reason.
+
+use std::{
+	collections::{HashMap, HashSet},
+	future::Future,
+	pin::Pin,
+	str::FromStr,
+	sync::Arc,
+};
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{DispatchClass, GetDispatchInfo},
+	traits::GetCallMetadata,
+	weights::Weight,
+};
+use node_template_runtime::{
+	opaque::Block, AccountId, BlockLength, BlockWeights, Hash, RuntimeCall, UncheckedExtrinsic,
+};
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use sc_consensus_aura::SlotProportion;
+use sc_transaction_pool_api::{
+	ImportNotificationStream, InPoolTransaction, PoolFuture, PoolStatus, ReadyTransactions,
+	TransactionFor, TransactionPool, TransactionSource, TransactionStatusStreamFor, TxHash,
+};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+	transaction_validity::TransactionTag,
+	Percent,
+};
+
+use crate::{pool::sender, service::FullClient};
+
+/// Options for building blocks.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ProposerConfiguration {
+	/// Percent of the block size allowed to normal transactions to fill.
+	#[arg(
+		long,
+		value_name = "PERCENT",
+		default_value = "100",
+		value_parser = clap::value_parser!(u8).range(1..=100),
+	)]
+	pub block_size_percent: u8,
+
+	/// Percent of the block weight allowed to normal transactions to fill.
+	#[arg(
+		long,
+		value_name = "PERCENT",
+		default_value = "100",
+		value_parser = clap::value_parser!(u8).range(1..=100),
+	)]
+	pub block_weight_percent: u8,
+
+	/// Maximum number of transactions of a sender in a block.
+	#[arg(long, value_name = "COUNT")]
+	pub max_sender_transactions: Option<u32>,
+
+	/// Offer the transactions of a call to blocks first, given as `Pallet` or `Pallet::call`,
+	/// e.g. `TemplateModule`. Can be repeated.
+	#[arg(long, value_name = "CALL")]
+	pub priority_call: Vec<CallName>,
+
+	/// Percent of the slot spent building a block, two thirds by default.
+	#[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=100))]
+	pub proposal_deadline: Option<u8>,
+}
+
+impl ProposerConfiguration {
+	/// The share of the slot spent building a block.
+	pub fn slot_proportion(&self) -> SlotProportion {
+		let proportion =
+			self.proposal_deadline.map_or(2f32 / 3f32, |percent| percent as f32 / 100f32);
+		SlotProportion::new(proportion)
+	}
+}
+
+/// A pallet, or a call of it, by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallName {
+	/// The name of the pallet, as in `construct_runtime!`.
+	pub pallet: String,
+	/// The name of the call, or `None` for all the calls of the pallet.
+	pub call: Option<String>,
+}
+
+impl CallName {
+	fn matches(&self, (pallet, call): (&str, &str)) -> bool {
+		self.pallet == pallet && self.call.as_ref().map_or(true, |name| name == call)
+	}
+
+	/// Check that the runtime has the pallet and the call.
+	fn check(&self) -> Result<(), String> {
+		if !RuntimeCall::get_module_names().iter().any(|pallet| *pallet == self.pallet) {
+			return Err(format!("Unknown pallet {}", self.pallet))
+		}
+		let calls = RuntimeCall::get_call_names(&self.pallet);
+		match &self.call {
+			Some(call) if !calls.iter().any(|name| name == call) =>
+				Err(format!("Unknown call {}::{}", self.pallet, call)),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl FromStr for CallName {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (pallet, call) = match s.split_once("::") {
+			Some((pallet, call)) => (pallet, Some(call.to_string())),
+			None => (s, None),
+		};
+		if pallet.is_empty() || call.as_deref() == Some("") {
+			return Err(format!("Expected `Pallet` or `Pallet::call`, got `{}`", s))
+		}
+		Ok(Self { pallet: pallet.to_string(), call })
+	}
+}
+
+/// A ready transaction, as a [`SelectionPolicy`] sees it.
+#[derive(Debug, Clone, Default)]
+pub struct Candidate {
+	/// The sender, if signed.
+	pub sender: Option<AccountId>,
+	/// The names of the pallet and of the call, if the transaction decodes.
+	pub call: Option<(&'static str, &'static str)>,
+	/// The encoded size.
+	pub size: u32,
+	/// The weight, as the native runtime estimates it.
+	pub weight: Weight,
+	/// The tags of the transactions it depends on.
+	pub requires: Vec<TransactionTag>,
+	/// The tags the transactions depending on it require.
+	pub provides: Vec<TransactionTag>,
+}
+
+/// Why a ready transaction was not offered to the block builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skip {
+	/// A transaction it depends on was skipped.
+	Dependency,
+	/// Its sender already has the maximum number of transactions in the block.
+	SenderCap,
+	/// It would exceed the block size.
+	BlockSize,
+	/// It would exceed the block weight.
+	BlockWeight,
+}
+
+impl Skip {
+	/// The label of the reason in metrics.
+	fn label(&self) -> &'static str {
+		match self {
+			Self::Dependency => "dependency",
+			Self::SenderCap => "sender_cap",
+			Self::BlockSize => "block_size",
+			Self::BlockWeight => "block_weight",
+		}
+	}
+}
+
+/// The transactions a [`SelectionPolicy`] selected, by their index among the candidates.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+	/// The transactions to offer, in order.
+	pub offered: Vec<usize>,
+	/// The transactions skipped, and why.
+	pub skipped: Vec<(usize, Skip)>,
+}
+
+/// Chooses which ready transactions are offered to the block builder, and in which order.
+pub trait SelectionPolicy: Send + Sync + 'static {
+	/// Select among `candidates`, which come in the order of the pool: by priority, after the
+	/// transactions they depend on. The transactions offered must keep coming after the ones they
+	/// depend on, or the block builder rejects them as invalid.
+	fn select(&self, candidates: &[Candidate]) -> Selection;
+}
+
+/// What the transactions offered so far take of the block.
+#[derive(Default)]
+struct Filled<'a> {
+	size: u32,
+	weight: Weight,
+	senders: HashMap<&'a AccountId, u32>,
+	offered: HashSet<&'a TransactionTag>,
+	skipped: HashSet<&'a TransactionTag>,
+}
+
+/// The selection policy configured with [`ProposerConfiguration`].
+pub struct BlockPolicy {
+	max_size: u32,
+	max_weight: Weight,
+	max_sender_transactions: Option<u32>,
+	priority_calls: Vec<CallName>,
+}
+
+impl BlockPolicy {
+	/// Create a new `BlockPolicy` from `config`, with the block limits of the runtime.
+	pub fn new(config: &ProposerConfiguration) -> Result<Self, String> {
+		for call in &config.priority_call {
+			call.check()?;
+		}
+
+		let weights = BlockWeights::get();
+		let max_weight = weights.get(DispatchClass::Normal).max_total.unwrap_or(weights.max_block);
+		let max_size = *BlockLength::get().max.get(DispatchClass::Normal);
+		Ok(Self {
+			max_size: Percent::from_percent(config.block_size_percent) * max_size,
+			max_weight: Percent::from_percent(config.block_weight_percent) * max_weight,
+			max_sender_transactions: config.max_sender_transactions,
+			priority_calls: config.priority_call.clone(),
+		})
+	}
+
+	fn is_priority(&self, candidate: &Candidate) -> bool {
+		candidate
+			.call
+			.map_or(false, |call| self.priority_calls.iter().any(|name| name.matches(call)))
+	}
+
+	fn skip(&self, filled: &Filled, candidate: &Candidate) -> Option<Skip> {
+		let sender_transactions =
+			candidate.sender.as_ref().and_then(|sender| filled.senders.get(sender)).copied();
+		if candidate.requires.iter().any(|tag| filled.skipped.contains(tag)) {
+			Some(Skip::Dependency)
+		} else if self
+			.max_sender_transactions
+			.map_or(false, |max| sender_transactions.unwrap_or(0) >= max)
+		{
+			Some(Skip::SenderCap)
+		} else if filled.size.saturating_add(candidate.size) > self.max_size {
+			Some(Skip::BlockSize)
+		} else if filled.weight.saturating_add(candidate.weight).any_gt(self.max_weight) {
+			Some(Skip::BlockWeight)
+		} else {
+			None
+		}
+	}
+
+	fn offer<'a>(
+		&self,
+		filled: &mut Filled<'a>,
+		selection: &mut Selection,
+		index: usize,
+		candidate: &'a Candidate,
+	) {
+		match self.skip(filled, candidate) {
+			Some(skip) => {
+				filled.skipped.extend(&candidate.provides);
+				selection.skipped.push((index, skip));
+			},
+			None => {
+				filled.size += candidate.size;
+				filled.weight = filled.weight.saturating_add(candidate.weight);
+				if let Some(sender) = &candidate.sender {
+					*filled.senders.entry(sender).or_default() += 1;
+				}
+				filled.offered.extend(&candidate.provides);
+				selection.offered.push(index);
+			},
+		}
+	}
+}
+
+impl SelectionPolicy for BlockPolicy {
+	fn select(&self, candidates: &[Candidate]) -> Selection {
+		let provided: HashSet<_> =
+			candidates.iter().flat_map(|candidate| &candidate.provides).collect();
+		let mut filled = Filled::default();
+		let mut selection = Selection::default();
+
+		// Priority transactions first, once the transactions they depend on in the pool are.
+		let mut offered = vec![false; candidates.len()];
+		for (index, candidate) in candidates.iter().enumerate() {
+			let ready = candidate
+				.requires
+				.iter()
+				.all(|tag| !provided.contains(tag) || filled.offered.contains(tag));
+			if ready && self.is_priority(candidate) {
+				self.offer(&mut filled, &mut selection, index, candidate);
+				offered[index] = true;
+			}
+		}
+		for (index, candidate) in candidates.iter().enumerate() {
+			if !offered[index] {
+				self.offer(&mut filled, &mut selection, index, candidate);
+			}
+		}
+
+		selection
+	}
+}
+
+#[derive(Clone)]
+struct Metrics {
+	skipped: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			skipped: register(
+				CounterVec::new(
+					Opts::new(
+						"proposer_skipped_transactions",
+						"Ready transactions left out of the blocks built locally",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// The ready transactions selected, skipping the ones depending on transactions reported invalid.
+struct Selected<T> {
+	transactions: std::vec::IntoIter<Arc<T>>,
+	invalid: HashSet<TransactionTag>,
+}
+
+impl<T: InPoolTransaction> Iterator for Selected<T> {
+	type Item = Arc<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let transaction = self.transactions.next()?;
+			if !transaction.requires().iter().any(|tag| self.invalid.contains(tag)) {
+				return Some(transaction)
+			}
+			self.invalid.extend(transaction.provides().iter().cloned());
+		}
+	}
+}
+
+impl<T: InPoolTransaction> ReadyTransactions for Selected<T> {
+	fn report_invalid(&mut self, transaction: &Self::Item) {
+		self.invalid.extend(transaction.provides().iter().cloned());
+	}
+}
+
+/// The candidate of a ready `transaction`, in the state of the block `hash`.
+fn candidate<T>(client: &FullClient, hash: Hash, base_weight: Weight, transaction: &T) -> Candidate
+where
+	T: InPoolTransaction<Transaction = <Block as BlockT>::Extrinsic>,
+{
+	let encoded = transaction.data().encode();
+	let mut candidate = Candidate {
+		size: encoded.len() as u32,
+		requires: transaction.requires().to_vec(),
+		provides: transaction.provides().to_vec(),
+		..Default::default()
+	};
+	if let Ok(extrinsic) = UncheckedExtrinsic::decode(&mut &encoded[..]) {
+		let call = &extrinsic.0.function;
+		let metadata = call.get_call_metadata();
+		candidate.call = Some((metadata.pallet_name, metadata.function_name));
+		candidate.weight = base_weight.saturating_add(call.get_dispatch_info().weight);
+		candidate.sender = extrinsic
+			.0
+			.signature
+			.as_ref()
+			.and_then(|(address, _, _)| sender(client, hash, address));
+	}
+	candidate
+}
+
+/// A transaction pool offering the proposer the ready transactions a [`SelectionPolicy`] selects.
+pub struct SelectingPool<P, S> {
+	inner: Arc<P>,
+	client: Arc<FullClient>,
+	policy: Arc<S>,
+	metrics: Option<Metrics>,
+}
+
+impl<P, S> SelectingPool<P, S> {
+	/// Create a new `SelectingPool` selecting the ready transactions of `inner` with `policy`,
+	/// reporting skipped transactions to `registry`.
+	pub fn new(
+		inner: Arc<P>,
+		client: Arc<FullClient>,
+		policy: S,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		let metrics = registry.map(Metrics::register).transpose()?;
+		Ok(Self { inner, client, policy: Arc::new(policy), metrics })
+	}
+}
+
+impl<P, S> TransactionPool for SelectingPool<P, S>
+where
+	P: TransactionPool<Block = Block>,
+	P::InPoolTransaction: Send + Sync + 'static,
+	S: SelectionPolicy,
+{
+	type Block = P::Block;
+	type Hash = P::Hash;
+	type InPoolTransaction = P::InPoolTransaction;
+	type Error = P::Error;
+
+	fn submit_at(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		self.inner.submit_at(at, source, xts)
+	}
+
+	fn submit_one(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		self.inner.submit_one(at, source, xt)
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		self.inner.submit_and_watch(at, source, xt)
+	}
+
+	fn ready_at(
+		&self,
+		at: NumberFor<Self::Block>,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		let ready = self.inner.ready_at(at);
+		let client = self.client.clone();
+		let policy = self.policy.clone();
+		let metrics = self.metrics.clone();
+
+		Box::pin(async move {
+			let transactions: Vec<_> = ready.await.collect();
+			let hash = client.info().best_hash;
+			let base_weight = BlockWeights::get().get(DispatchClass::Normal).base_extrinsic;
+			let candidates: Vec<_> = transactions
+				.iter()
+				.map(|transaction| candidate(&client, hash, base_weight, &**transaction))
+				.collect();
+
+			let selection = policy.select(&candidates);
+			log::debug!(
+				target: "txpool",
+				"Offering {} of the {} ready transactions to the block",
+				selection.offered.len(),
+				transactions.len(),
+			);
+			if let Some(metrics) = &metrics {
+				for (_, skip) in &selection.skipped {
+					metrics.skipped.with_label_values(&[skip.label()]).inc();
+				}
+			}
+
+			let offered: Vec<_> =
+				selection.offered.iter().map(|&index| transactions[index].clone()).collect();
+			Box::new(Selected { transactions: offered.into_iter(), invalid: HashSet::new() })
+				as Box<dyn ReadyTransactions<Item = Arc<P::InPoolTransaction>> + Send>
+		})
+	}
+
+	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
+		self.inner.ready()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		if let Some(metrics) = &self.metrics {
+			metrics.skipped.with_label_values(&["invalid"]).inc_by(hashes.len() as u64);
+		}
+		self.inner.remove_invalid(hashes)
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.inner.status()
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		self.inner.import_notification_stream()
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		self.inner.on_broadcasted(propagations)
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.inner.hash_of(xt)
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.inner.ready_transaction(hash)
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		self.inner.futures()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+
+	fn policy(max_sender_transactions: Option<u32>, priority_calls: &[&str]) -> BlockPolicy {
+		BlockPolicy {
+			max_size: 100,
+			max_weight: Weight::from_ref_time(100),
+			max_sender_transactions,
+			priority_calls: priority_calls.iter().map(|call| call.parse().unwrap()).collect(),
+		}
+	}
+
+	/// A transaction of `sender` with `nonce`, depending on the previous nonce.
+	fn transaction(sender: AccountKeyring, nonce: u8, call: &'static str) -> Candidate {
+		let tag = |nonce: u8| vec![sender as u8, nonce];
+		Candidate {
+			sender: Some(sender.to_account_id()),
+			call: Some(("Balances", call)),
+			size: 10,
+			weight: Weight::from_ref_time(10),
+			requires: if nonce > 0 { vec![tag(nonce - 1)] } else { Vec::new() },
+			provides: vec![tag(nonce)],
+		}
+	}
+
+	#[test]
+	fn call_names_parse() {
+		let pallet = CallName { pallet: "TemplateModule".into(), call: None };
+		let call = CallName { pallet: "Balances".into(), call: Some("transfer".into()) };
+
+		assert_eq!("TemplateModule".parse(), Ok(pallet));
+		assert_eq!("Balances::transfer".parse(), Ok(call));
+		assert!("Balances::".parse::<CallName>().is_err());
+		assert!("::transfer".parse::<CallName>().is_err());
+		assert_eq!("Balances::transfer".parse::<CallName>().unwrap().check(), Ok(()));
+		assert!("Balances::nope".parse::<CallName>().unwrap().check().is_err());
+	}
+
+	#[test]
+	fn senders_are_capped_with_their_dependent_transactions() {
+		let candidates = [
+			transaction(AccountKeyring::Alice, 0, "transfer"),
+			transaction(AccountKeyring::Alice, 1, "transfer"),
+			transaction(AccountKeyring::Bob, 0, "transfer"),
+			transaction(AccountKeyring::Alice, 2, "transfer"),
+		];
+
+		assert_eq!(
+			policy(Some(1), &[]).select(&candidates),
+			Selection {
+				offered: vec![0, 2],
+				skipped: vec![(1, Skip::SenderCap), (3, Skip::Dependency)],
+			},
+		);
+		assert_eq!(policy(None, &[]).select(&candidates).offered, vec![0, 1, 2, 3]);
+	}
+
+	#[test]
+	fn blocks_are_filled_up_to_the_limits() {
+		let mut candidates: Vec<_> = (0..12)
+			.map(|nonce| transaction(AccountKeyring::Alice, nonce, "transfer"))
+			.collect();
+		// Heavier than the rest of the block, but the next one still fits.
+		candidates[9].requires.clear();
+		candidates[9].provides = vec![vec![0xff]];
+		candidates[9].weight = Weight::from_ref_time(20);
+
+		let selection = policy(None, &[]).select(&candidates);
+		assert_eq!(selection.offered, (0..9).chain(Some(10)).collect::<Vec<_>>());
+		assert_eq!(selection.skipped, vec![(9, Skip::BlockWeight), (11, Skip::BlockSize)]);
+	}
+
+	#[test]
+	fn priority_calls_come_first_after_their_dependencies() {
+		let candidates = [
+			transaction(AccountKeyring::Alice, 0, "transfer"),
+			transaction(AccountKeyring::Bob, 0, "transfer"),
+			transaction(AccountKeyring::Bob, 1, "transfer_keep_alive"),
+			transaction(AccountKeyring::Charlie, 0, "transfer_keep_alive"),
+		];
+
+		assert_eq!(
+			policy(None, &["Balances::transfer_keep_alive"]).select(&candidates).offered,
+			vec![3, 0, 1, 2],
+		);
+		assert_eq!(policy(None, &["Balances"]).select(&candidates).offered, vec![0, 1, 2, 3]);
+	}
+}
@@ -8,50 +8,179 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use node_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index};
+use sc_client_api::{
+	backend::{AuxStore, Backend, StateBackend, StateBackendFor, StorageProvider},
+	client::BlockchainEvents,
+};
+use sc_finality_grandpa::{
+	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
+};
+use sc_rpc::SubscriptionTaskExecutor;
+use sc_transaction_pool::ChainApi;
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{CallApiAt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT};
+
+use crate::{authoring::AuthoringSwitch, indexer::IndexDb};
 
 pub use sc_rpc_api::DenyUnsafe;
 
+pub mod accounts;
+pub mod admin;
+pub mod contracts;
+pub mod dry_run;
+pub mod events;
+pub mod eth;
+pub mod identity;
+
+pub use eth::{overrides_handle, EthDeps};
+
+/// Dependencies of the admin RPC methods.
+pub struct AdminDeps {
+	/// The keystore holding the local authority keys.
+	pub keystore: SyncCryptoStorePtr,
+	/// The switch pausing the local authoring.
+	pub authoring: AuthoringSwitch,
+	/// The state of the local GRANDPA voter.
+	pub shared_voter_state: SharedVoterState,
+}
+
+/// Dependencies of the GRANDPA RPC methods.
+pub struct GrandpaDeps<BE> {
+	/// Voting round info.
+	pub shared_voter_state: SharedVoterState,
+	/// Authority set info.
+	pub shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+	/// Receives notifications about justification events from GRANDPA.
+	pub justification_stream: GrandpaJustificationStream<Block>,
+	/// Executor to drive the subscription manager in the GRANDPA RPC handler.
+	pub subscription_executor: SubscriptionTaskExecutor,
+	/// Finality proof provider.
+	pub finality_provider: Arc<FinalityProofProvider<BE, Block>>,
+}
+
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, BE, A: ChainApi, CT> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend of the client.
+	pub backend: Arc<BE>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// The event index, if the node keeps one.
+	pub event_index: Option<Arc<IndexDb>>,
+	/// The account history index, if the node keeps one.
+	pub history_index: Option<Arc<IndexDb>>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Admin specific dependencies.
+	pub admin: AdminDeps,
+	/// GRANDPA specific dependencies.
+	pub grandpa: GrandpaDeps<BE>,
+	/// Ethereum-compatibility specific dependencies.
+	pub eth: EthDeps<C, P, A, CT, Block>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, BE, A, CT>(
+	deps: FullDeps<C, P, BE, A, CT>,
+	subscription_task_executor: SubscriptionTaskExecutor,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
-	C: ProvideRuntimeApi<Block>,
+	C: ProvideRuntimeApi<Block> + CallApiAt<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+	C: StorageProvider<Block, BE> + AuxStore + BlockchainEvents<Block>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>,
+	C::Api: node_template_runtime::apis::IdentityApi<Block, AccountId>,
+	C::Api: node_template_runtime::apis::DryRunApi<
+		Block,
+		node_template_runtime::RuntimeCall,
+		node_template_runtime::RuntimeEvent,
+		AccountId,
+		Balance,
+	>,
+	C::Api: sp_consensus_aura::AuraApi<Block, AuraId>,
+	C::Api: sp_api::ApiExt<Block, StateBackend = StateBackendFor<BE, Block>>,
+	C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
+	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + 'static,
+	BE: Backend<Block> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	P: TransactionPool<Block = Block> + 'static,
+	A: ChainApi<Block = Block> + 'static,
+	CT: fp_rpc::ConvertTransaction<<Block as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
+	use accounts::{AccountHistory, AccountHistoryApiServer};
+	use admin::{Admin, AdminApiServer};
+	use contracts::{Contracts, ContractsApiServer};
+	use dry_run::{DryRun, DryRunApiServer};
+	use events::{Events, EventsApiServer};
+	use identity::{Identity, IdentityApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use sc_finality_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps {
+		client,
+		backend,
+		pool,
+		event_index,
+		history_index,
+		deny_unsafe,
+		admin,
+		grandpa,
+		eth,
+	} = deps;
+	let GrandpaDeps {
+		shared_voter_state,
+		shared_authority_set,
+		justification_stream,
+		subscription_executor,
+		finality_provider,
+	} = grandpa;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Contracts::new(client.clone()).into_rpc())?;
+	module.merge(Identity::new(client.clone()).into_rpc())?;
+	module.merge(DryRun::new(client.clone(), backend, deny_unsafe).into_rpc())?;
+	module.merge(
+		Admin::new(client, admin.keystore, admin.authoring, admin.shared_voter_state, deny_unsafe)
+			.into_rpc(),
+	)?;
+	module.merge(
+		Grandpa::new(
+			subscription_executor,
+			shared_authority_set,
+			shared_voter_state,
+			justification_stream,
+			finality_provider,
+		)
+		.into_rpc(),
+	)?;
+	if let Some(event_index) = event_index {
+		module.merge(Events::new(event_index).into_rpc())?;
+	}
+	if let Some(history_index) = history_index {
+		module.merge(AccountHistory::new(history_index).into_rpc())?;
+	}
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
 	// to call into the runtime.
 	// `module.merge(YourRpcTrait::into_rpc(YourRpcStruct::new(ReferenceToClient, ...)))?;`
 
+	// Ethereum compatibility RPCs
+	let module = eth::create_eth(module, eth, subscription_task_executor)?;
+
 	Ok(module)
 }
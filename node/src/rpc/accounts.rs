@@ -0,0 +1,151 @@
+//! RPC interface querying the node-side account history index.
+//!
+//! Only available on nodes running with `--index-accounts`.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{AccountId, BlockNumber, Hash, Index};
+use serde::{Deserialize, Serialize};
+use sp_runtime::DispatchError;
+
+use crate::indexer::{accounts, IndexDb};
+
+/// The number of extrinsics returned when the request doesn't say.
+const DEFAULT_LIMIT: u32 = 100;
+/// The maximum number of extrinsics returned at once.
+const MAX_LIMIT: u32 = 1_000;
+
+/// The position of an extrinsic in the chain, to resume a query from.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct ExtrinsicCursor {
+	/// The number of the block of the extrinsic.
+	block_number: BlockNumber,
+	/// The index of the extrinsic in its block.
+	index: u32,
+}
+
+/// An extrinsic signed by the account.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extrinsic {
+	/// The number of the block of the extrinsic.
+	pub block_number: BlockNumber,
+	/// The hash of the block of the extrinsic.
+	pub block_hash: Hash,
+	/// The index of the extrinsic in its block.
+	pub index: u32,
+	/// The hash of the extrinsic.
+	pub hash: Hash,
+	/// The nonce the extrinsic was signed with.
+	pub nonce: Index,
+	/// The index of the pallet of the call, as in `construct_runtime!`.
+	pub pallet_index: u8,
+	/// The index of the call in its pallet.
+	pub call_index: u8,
+	/// Whether the call succeeded.
+	pub success: bool,
+	/// Why the call failed, if it did.
+	pub error: Option<DispatchError>,
+}
+
+/// A page of the history of an account.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+	/// The extrinsics, in chain order.
+	pub extrinsics: Vec<Extrinsic>,
+	/// Where the next page starts, if there are more extrinsics.
+	pub next: Option<ExtrinsicCursor>,
+}
+
+/// Account history RPC methods.
+#[rpc(server)]
+pub trait AccountHistoryApi {
+	/// Returns the extrinsics `account` signed, in chain order, starting from `cursor` and up to
+	/// `limit` of them (100 by default, 1000 at most).
+	///
+	/// `next` in the result is the cursor of the next page.
+	#[method(name = "account_history")]
+	fn history(
+		&self,
+		account: AccountId,
+		cursor: Option<ExtrinsicCursor>,
+		limit: Option<u32>,
+	) -> RpcResult<HistoryPage>;
+}
+
+/// Provides RPC methods to query the account history index.
+pub struct AccountHistory {
+	db: Arc<IndexDb>,
+}
+
+impl AccountHistory {
+	/// Create new `AccountHistory` with the given reference to the account history index.
+	pub fn new(db: Arc<IndexDb>) -> Self {
+		Self { db }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The index could not be read.
+	IndexError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::IndexError => 1,
+		}
+	}
+}
+
+impl AccountHistoryApiServer for AccountHistory {
+	fn history(
+		&self,
+		account: AccountId,
+		cursor: Option<ExtrinsicCursor>,
+		limit: Option<u32>,
+	) -> RpcResult<HistoryPage> {
+		let start = cursor.map(|cursor| (cursor.block_number, cursor.index));
+		let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+		let (found, next) =
+			accounts::history(&self.db, &account, start, limit as usize).map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::IndexError.into(),
+					"Unable to query the account history index.",
+					Some(e),
+				))
+			})?;
+
+		let extrinsics = found
+			.into_iter()
+			.map(|found| {
+				let (block_number, index) = found.position;
+				let (pallet_index, call_index) = found.extrinsic.call;
+				Extrinsic {
+					block_number,
+					block_hash: found.block_hash,
+					index,
+					hash: found.extrinsic.hash,
+					nonce: found.extrinsic.nonce,
+					pallet_index,
+					call_index,
+					success: found.extrinsic.result.is_ok(),
+					error: found.extrinsic.result.err(),
+				}
+			})
+			.collect();
+		let next = next.map(|(block_number, index)| ExtrinsicCursor { block_number, index });
+
+		Ok(HistoryPage { extrinsics, next })
+	}
+}
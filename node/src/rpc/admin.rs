@@ -0,0 +1,236 @@
+//! RPC interface for node operators: pausing the local authoring, and inspecting consensus.
+//!
+//! All of these methods are unsafe.
+
+use std::{collections::HashSet, sync::Arc};
+
+use finality_grandpa::voter::report;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::opaque::Block;
+use sc_finality_grandpa::SharedVoterState;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{sr25519::AuthorityId as AuraId, AuraApi, Slot};
+use sp_core::crypto::{key_types::AURA, ByteArray};
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+
+use crate::authoring::AuthoringSwitch;
+
+/// The number of slots returned when the request doesn't say.
+const DEFAULT_COUNT: u32 = 10;
+/// The maximum number of slots returned at once.
+const MAX_COUNT: u32 = 1_000;
+
+/// A slot the local keys can claim.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimableSlot {
+	/// The slot number.
+	pub slot: u64,
+	/// When the slot starts, in milliseconds since the Unix epoch.
+	pub timestamp: u64,
+	/// The local authority key claiming the slot.
+	pub authority: AuraId,
+}
+
+/// The authoring status of the node.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthoringStatus {
+	/// Whether authoring is paused. Paused nodes don't claim the slots below.
+	pub paused: bool,
+	/// The current slot.
+	pub current_slot: u64,
+	/// The next slots the local keys can claim, with the current authorities.
+	pub claimable_slots: Vec<ClaimableSlot>,
+}
+
+/// The votes of a GRANDPA round.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundVotes {
+	/// The round number.
+	pub round: u64,
+	/// The total weight of the voters.
+	pub total_weight: u64,
+	/// The weight needed to complete the round.
+	pub threshold_weight: u64,
+	/// The weight of the prevotes received.
+	pub prevote_weight: u64,
+	/// The voters who prevoted.
+	pub prevotes: Vec<GrandpaId>,
+	/// The weight of the precommits received.
+	pub precommit_weight: u64,
+	/// The voters who precommitted.
+	pub precommits: Vec<GrandpaId>,
+}
+
+/// The state of the local GRANDPA voter.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterState {
+	/// The round the voter is in.
+	pub best: RoundVotes,
+	/// The previous rounds the voter still follows.
+	pub background: Vec<RoundVotes>,
+}
+
+/// Admin RPC methods.
+#[rpc(server)]
+pub trait AdminApi {
+	/// Stop authoring blocks until `admin_resumeAuthoring`. The node keeps importing and
+	/// finalizing blocks, as an observer.
+	#[method(name = "admin_pauseAuthoring")]
+	fn pause_authoring(&self) -> RpcResult<()>;
+
+	/// Author blocks again after `admin_pauseAuthoring`.
+	#[method(name = "admin_resumeAuthoring")]
+	fn resume_authoring(&self) -> RpcResult<()>;
+
+	/// Returns whether authoring is paused, and the next `count` slots (10 by default, 1000 at
+	/// most) the local Aura keys can claim with the authorities of the best block.
+	#[method(name = "admin_authoringStatus")]
+	fn authoring_status(&self, count: Option<u32>) -> RpcResult<AuthoringStatus>;
+
+	/// Returns the votes of the rounds the local GRANDPA voter is in, if it runs.
+	#[method(name = "admin_grandpaVoterState")]
+	fn grandpa_voter_state(&self) -> RpcResult<Option<VoterState>>;
+}
+
+/// Provides admin RPC methods.
+pub struct Admin<C> {
+	client: Arc<C>,
+	keystore: SyncCryptoStorePtr,
+	authoring: AuthoringSwitch,
+	shared_voter_state: SharedVoterState,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> Admin<C> {
+	/// Create new `Admin` with the given reference to the client, the keystore, the authoring
+	/// switch and the state of the GRANDPA voter.
+	pub fn new(
+		client: Arc<C>,
+		keystore: SyncCryptoStorePtr,
+		authoring: AuthoringSwitch,
+		shared_voter_state: SharedVoterState,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, keystore, authoring, shared_voter_state, deny_unsafe }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: sp_api::ApiError) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the Aura authorities.",
+		Some(e.to_string()),
+	))
+	.into()
+}
+
+fn round_votes(round: u64, state: &report::RoundState<GrandpaId>) -> RoundVotes {
+	RoundVotes {
+		round,
+		total_weight: state.total_weight.get(),
+		threshold_weight: state.threshold_weight.get(),
+		prevote_weight: state.prevote_current_weight.0,
+		prevotes: state.prevote_ids.iter().cloned().collect(),
+		precommit_weight: state.precommit_current_weight.0,
+		precommits: state.precommit_ids.iter().cloned().collect(),
+	}
+}
+
+impl<C> AdminApiServer for Admin<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AuraApi<Block, AuraId>,
+{
+	fn pause_authoring(&self) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+		self.authoring.pause();
+		Ok(())
+	}
+
+	fn resume_authoring(&self) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+		self.authoring.resume();
+		Ok(())
+	}
+
+	fn authoring_status(&self, count: Option<u32>) -> RpcResult<AuthoringStatus> {
+		self.deny_unsafe.check_if_safe()?;
+		let count = count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT) as usize;
+
+		let api = self.client.runtime_api();
+		let best_hash = self.client.info().best_hash;
+		let slot_duration = api.slot_duration(best_hash).map_err(runtime_error)?;
+		let authorities = api.authorities(best_hash).map_err(runtime_error)?;
+		let current_slot = Slot::from_timestamp(sp_timestamp::Timestamp::current(), slot_duration);
+
+		// Aura assigns slots to authorities in turn, so the local keys claim the same positions
+		// in every round of the authorities.
+		let local: HashSet<usize> = (0..authorities.len())
+			.filter(|&index| {
+				let key = authorities[index].to_raw_vec();
+				SyncCryptoStore::has_keys(&*self.keystore, &[(key, AURA)])
+			})
+			.collect();
+		let claimable_slots = if local.is_empty() {
+			Vec::new()
+		} else {
+			(*current_slot + 1..)
+				.filter(|slot| local.contains(&((slot % authorities.len() as u64) as usize)))
+				.take(count)
+				.map(|slot| ClaimableSlot {
+					slot,
+					timestamp: slot * slot_duration.as_millis(),
+					authority: authorities[(slot % authorities.len() as u64) as usize].clone(),
+				})
+				.collect()
+		};
+
+		Ok(AuthoringStatus {
+			paused: self.authoring.is_paused(),
+			current_slot: *current_slot,
+			claimable_slots,
+		})
+	}
+
+	fn grandpa_voter_state(&self) -> RpcResult<Option<VoterState>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.shared_voter_state.voter_state().map(|state| {
+			let (round, best) = &state.best_round;
+			let mut background: Vec<_> = state
+				.background_rounds
+				.iter()
+				.map(|(round, votes)| round_votes(*round, votes))
+				.collect();
+			background.sort_by_key(|votes| votes.round);
+
+			VoterState { best: round_votes(*round, best), background }
+		}))
+	}
+}
@@ -0,0 +1,249 @@
+//! RPC interface for the contracts pallet.
+//!
+//! Thin JSON wrapper over [`pallet_contracts::ContractsApi`]. Results are returned SCALE encoded,
+//! exactly as `state_call` would return them, so existing contract tooling can decode them.
+
+use std::sync::Arc;
+
+use codec::{Codec, Encode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::Weight;
+use pallet_contracts::{ContractsApi as ContractsRuntimeApi, Determinism};
+use pallet_contracts_primitives::Code;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+
+/// A request to call a contract.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CallRequest<AccountId, Balance> {
+	origin: AccountId,
+	dest: AccountId,
+	value: Balance,
+	gas_limit: Option<Weight>,
+	storage_deposit_limit: Option<Balance>,
+	input_data: Bytes,
+}
+
+/// A request to instantiate a contract, either from uploaded code or an existing code hash.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Hash, Balance> {
+	origin: AccountId,
+	value: Balance,
+	gas_limit: Option<Weight>,
+	storage_deposit_limit: Option<Balance>,
+	code: Code<Hash>,
+	data: Bytes,
+	salt: Bytes,
+}
+
+/// A request to upload a contract's code without instantiating it.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CodeUploadRequest<AccountId, Balance> {
+	origin: AccountId,
+	code: Bytes,
+	storage_deposit_limit: Option<Balance>,
+}
+
+/// Contracts RPC methods.
+#[rpc(server)]
+pub trait ContractsApi<BlockHash, AccountId, Balance> {
+	/// Dry-run a contract call. Returns the SCALE encoded `ContractExecResult`.
+	#[method(name = "contracts_call")]
+	fn call(
+		&self,
+		call_request: CallRequest<AccountId, Balance>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Bytes>;
+
+	/// Dry-run a contract instantiation. Returns the SCALE encoded `ContractInstantiateResult`.
+	#[method(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, BlockHash, Balance>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Bytes>;
+
+	/// Dry-run a code upload. Returns the SCALE encoded `CodeUploadResult`.
+	#[method(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest<AccountId, Balance>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Bytes>;
+
+	/// Returns the value under `key` in the storage of the contract at `address`, if any.
+	#[method(name = "contracts_getStorage")]
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Bytes>>;
+}
+
+/// Provides RPC methods to query a contract's state.
+pub struct Contracts<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Contracts<C, B> {
+	/// Create new `Contracts` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The contract's storage could not be read.
+	StorageError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::StorageError => 2,
+		}
+	}
+}
+
+fn runtime_error(message: &'static str, e: impl std::fmt::Display) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), message, Some(e.to_string())))
+		.into()
+}
+
+impl<C, Block, AccountId, Balance> ContractsApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+	for Contracts<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+		<Block as BlockT>::Hash,
+	>,
+	AccountId: Codec + Send + Sync + 'static,
+	Balance: Codec + Send + Sync + 'static,
+{
+	fn call(
+		&self,
+		call_request: CallRequest<AccountId, Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Bytes> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let CallRequest { origin, dest, value, gas_limit, storage_deposit_limit, input_data } =
+			call_request;
+		let result = api
+			.call(
+				at_hash,
+				origin,
+				dest,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				input_data.to_vec(),
+			)
+			.map_err(|e| runtime_error("Unable to dry-run contract call.", e))?;
+
+		Ok(result.encode().into())
+	}
+
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, <Block as BlockT>::Hash, Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Bytes> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let InstantiateRequest {
+			origin,
+			value,
+			gas_limit,
+			storage_deposit_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+		let result = api
+			.instantiate(
+				at_hash,
+				origin,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data.to_vec(),
+				salt.to_vec(),
+			)
+			.map_err(|e| runtime_error("Unable to dry-run contract instantiation.", e))?;
+
+		Ok(result.encode().into())
+	}
+
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest<AccountId, Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Bytes> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let CodeUploadRequest { origin, code, storage_deposit_limit } = upload_request;
+		let result = api
+			.upload_code(
+				at_hash,
+				origin,
+				code.to_vec(),
+				storage_deposit_limit,
+				Determinism::Deterministic,
+			)
+			.map_err(|e| runtime_error("Unable to dry-run code upload.", e))?;
+
+		Ok(result.encode().into())
+	}
+
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let result = api
+			.get_storage(at_hash, address, key.to_vec())
+			.map_err(|e| runtime_error("Unable to query contract storage.", e))?
+			.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::StorageError.into(),
+					"Contract storage is not accessible.",
+					Some(format!("{:?}", e)),
+				))
+			})?;
+
+		Ok(result.map(Bytes))
+	}
+}
@@ -0,0 +1,291 @@
+//! RPC interface previewing what calls and extrinsics would do, without committing anything.
+//!
+//! Wraps the runtime's [`DryRunApi`](DryRunRuntimeApi), adding the storage keys the dry run
+//! changed. Calls, extrinsics and events are SCALE encoded, as for `state_call`.
+
+use std::{collections::HashMap, sync::Arc};
+
+use codec::{Codec, DecodeLimit, Encode};
+use frame_system::RawOrigin;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{
+	apis::{DryRunApi as DryRunRuntimeApi, DryRunEffects},
+	RuntimeCall, RuntimeEvent, Weight,
+};
+use sc_client_api::backend::{Backend, StateBackendFor};
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ApiRef, Core, ProvideRuntimeApi, MAX_EXTRINSIC_DEPTH};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, One},
+	transaction_validity::TransactionValidityError,
+	DispatchError,
+};
+
+/// The origin to dispatch a dry-run call from.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DryRunOrigin<AccountId> {
+	/// The root origin.
+	Root,
+	/// An origin signed by the given account.
+	Signed(AccountId),
+	/// The origin of unsigned transactions and inherents.
+	None,
+}
+
+impl<AccountId> From<DryRunOrigin<AccountId>> for RawOrigin<AccountId> {
+	fn from(origin: DryRunOrigin<AccountId>) -> Self {
+		match origin {
+			DryRunOrigin::Root => RawOrigin::Root,
+			DryRunOrigin::Signed(who) => RawOrigin::Signed(who),
+			DryRunOrigin::None => RawOrigin::None,
+		}
+	}
+}
+
+/// The keys a dry run changed in a child trie.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildTrieChanges {
+	/// The storage key of the child trie, without the default child trie prefix.
+	pub child_trie: Bytes,
+	/// The keys changed in the child trie.
+	pub keys: Vec<Bytes>,
+}
+
+/// What a dry run did.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult<Balance> {
+	/// The outcome of the dispatch.
+	pub result: Result<(), DispatchError>,
+	/// The SCALE encoded `RuntimeEvent`s deposited by the dispatch.
+	pub events: Vec<Bytes>,
+	/// The weight the dispatch consumed, after refunds.
+	pub actual_weight: Weight,
+	/// The fee charged for the dispatch, tip included.
+	pub fee: Balance,
+	/// The keys changed in the main trie.
+	pub changed_keys: Vec<Bytes>,
+	/// The keys changed in child tries, e.g. by contracts.
+	pub changed_child_keys: Vec<ChildTrieChanges>,
+}
+
+/// Dry-run RPC methods.
+#[rpc(server)]
+pub trait DryRunApi<BlockHash, AccountId, Balance> {
+	/// Dispatch the SCALE encoded `RuntimeCall` `call` from `origin`, on top of the state at `at`.
+	///
+	/// No transaction checks are made: the signer's nonce isn't checked and no fee is withdrawn.
+	/// The reported fee is what the call would cost as a transaction signed with an sr25519 key,
+	/// without tip.
+	///
+	/// Dry runs from the root and none origins are unsafe, as they skip every origin check.
+	#[method(name = "dryRun_call")]
+	fn call(
+		&self,
+		origin: DryRunOrigin<AccountId>,
+		call: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<DryRunResult<Balance>>;
+
+	/// Apply the SCALE encoded extrinsic `extrinsic` on top of the state at `at`, with all its
+	/// checks and fee payment.
+	#[method(name = "dryRun_extrinsic")]
+	fn extrinsic(
+		&self,
+		extrinsic: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<DryRunResult<Balance>>;
+}
+
+/// Provides RPC methods to dry-run calls and extrinsics.
+pub struct DryRun<C, BE, B> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, BE, B> DryRun<C, BE, B> {
+	/// Create new `DryRun` with the given reference to the client and to its backend.
+	pub fn new(client: Arc<C>, backend: Arc<BE>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { client, backend, deny_unsafe, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The call or extrinsic could not be decoded.
+	DecodeError,
+	/// The extrinsic is not valid, and would not be included in a block.
+	InvalidTransaction,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::DecodeError => 2,
+			Error::InvalidTransaction => 3,
+		}
+	}
+}
+
+fn error(kind: Error, message: &'static str, e: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(kind.into(), message, Some(format!("{:?}", e)))).into()
+}
+
+/// What a runtime dry-run function returned.
+type DryRunOutcome<Balance> = Result<
+	Result<DryRunEffects<RuntimeEvent, Balance>, TransactionValidityError>,
+	sp_api::ApiError,
+>;
+
+/// The keys `changes` sets to something else than `baseline` does.
+fn changed_keys(
+	baseline: &[(Vec<u8>, Option<Vec<u8>>)],
+	changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+) -> Vec<Bytes> {
+	let baseline: HashMap<_, _> = baseline.iter().map(|(key, value)| (key, value)).collect();
+	changes
+		.into_iter()
+		.filter(|(key, value)| baseline.get(key) != Some(&value))
+		.map(|(key, _)| key.into())
+		.collect()
+}
+
+impl<C, BE, Block> DryRun<C, BE, Block>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: Core<Block> + ApiExt<Block, StateBackend = StateBackendFor<BE, Block>>,
+{
+	/// Run `dry_run` on top of a child of `at`, and collect what it did.
+	///
+	/// Initializing the child block runs `on_initialize` hooks, which change storage too. Their
+	/// changes are told apart by initializing the child block a second time, on its own.
+	fn dry_run<Balance>(
+		&self,
+		at: Option<Block::Hash>,
+		dry_run: impl FnOnce(&C::Api, Block::Hash) -> DryRunOutcome<Balance>,
+	) -> RpcResult<DryRunResult<Balance>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let number = self
+			.client
+			.number(at_hash)
+			.and_then(|number| {
+				number.ok_or_else(|| sp_blockchain::Error::UnknownBlock(at_hash.to_string()))
+			})
+			.map_err(|e| error(Error::RuntimeError, "Unable to find block.", e))?;
+		let header = <Block::Header as HeaderT>::new(
+			number + One::one(),
+			Default::default(),
+			Default::default(),
+			at_hash,
+			Default::default(),
+		);
+		let state = self
+			.backend
+			.state_at(at_hash)
+			.map_err(|e| error(Error::RuntimeError, "Unable to read state.", e))?;
+
+		let initialized = |api: &ApiRef<C::Api>| {
+			api.initialize_block(at_hash, &header)
+				.map_err(|e| error(Error::RuntimeError, "Unable to initialize block.", e))
+		};
+		let storage_changes = |api: ApiRef<C::Api>| {
+			api.into_storage_changes(&state, at_hash)
+				.map_err(|e| error(Error::RuntimeError, "Unable to collect storage changes.", e))
+		};
+
+		let api = self.client.runtime_api();
+		initialized(&api)?;
+		let baseline = storage_changes(api)?;
+
+		let api = self.client.runtime_api();
+		initialized(&api)?;
+		let effects = dry_run(&api, at_hash)
+			.map_err(|e| error(Error::RuntimeError, "Unable to dry-run.", e))?
+			.map_err(|e| error(Error::InvalidTransaction, "Invalid transaction.", e))?;
+		let changes = storage_changes(api)?;
+
+		let changed_child_keys = changes
+			.child_storage_changes
+			.into_iter()
+			.filter_map(|(child_trie, changes)| {
+				let baseline = baseline
+					.child_storage_changes
+					.iter()
+					.find(|(baseline_trie, _)| *baseline_trie == child_trie)
+					.map_or(&[][..], |(_, baseline)| &baseline[..]);
+				let keys = changed_keys(baseline, changes);
+				let child_trie = child_trie.into();
+				(!keys.is_empty()).then_some(ChildTrieChanges { child_trie, keys })
+			})
+			.collect();
+
+		Ok(DryRunResult {
+			result: effects.result,
+			events: effects.events.into_iter().map(|event| event.encode().into()).collect(),
+			actual_weight: effects.actual_weight,
+			fee: effects.fee,
+			changed_keys: changed_keys(
+				&baseline.main_storage_changes,
+				changes.main_storage_changes,
+			),
+			changed_child_keys,
+		})
+	}
+}
+
+impl<C, BE, Block, AccountId, Balance> DryRunApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+	for DryRun<C, BE, Block>
+where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: Core<Block>
+		+ DryRunRuntimeApi<Block, RuntimeCall, RuntimeEvent, AccountId, Balance>
+		+ ApiExt<Block, StateBackend = StateBackendFor<BE, Block>>,
+	AccountId: Codec + Send + Sync + 'static,
+	Balance: Codec + Send + Sync + 'static,
+{
+	fn call(
+		&self,
+		origin: DryRunOrigin<AccountId>,
+		call: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<DryRunResult<Balance>> {
+		if !matches!(origin, DryRunOrigin::Signed(_)) {
+			self.deny_unsafe.check_if_safe()?;
+		}
+
+		let call = RuntimeCall::decode_with_depth_limit(MAX_EXTRINSIC_DEPTH, &call[..])
+			.map_err(|e| error(Error::DecodeError, "Unable to decode call.", e))?;
+
+		self.dry_run(at, |api, at_hash| api.dry_run_call(at_hash, origin.into(), call).map(Ok))
+	}
+
+	fn extrinsic(
+		&self,
+		extrinsic: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<DryRunResult<Balance>> {
+		let extrinsic =
+			Block::Extrinsic::decode_with_depth_limit(MAX_EXTRINSIC_DEPTH, &extrinsic[..])
+				.map_err(|e| error(Error::DecodeError, "Unable to decode extrinsic.", e))?;
+
+		self.dry_run(at, |api, at_hash| api.dry_run_extrinsic(at_hash, extrinsic))
+	}
+}
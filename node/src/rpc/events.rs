@@ -0,0 +1,195 @@
+//! RPC interface querying the node-side event index.
+//!
+//! Only available on nodes running with `--index-events`.
+
+use std::sync::Arc;
+
+use codec::Encode;
+use frame_system::Phase;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{AccountId, BlockNumber, Hash};
+use serde::{Deserialize, Serialize};
+use sp_core::{Bytes, H256};
+
+use crate::indexer::{
+	events::{self, EventFilter, EventId},
+	IndexDb,
+};
+
+/// The number of events returned when the request doesn't say.
+const DEFAULT_LIMIT: u32 = 100;
+/// The maximum number of events returned at once.
+const MAX_LIMIT: u32 = 1_000;
+
+/// A query for indexed events.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct EventQuery {
+	/// The name of the pallet depositing the events, as in `construct_runtime!`.
+	pallet: String,
+	/// The name of the event variant.
+	variant: String,
+	/// Only return events with this account in their fields.
+	account: Option<AccountId>,
+	/// Only return events with this topic.
+	topic: Option<H256>,
+	/// The first block to look in. Defaults to genesis.
+	from: Option<BlockNumber>,
+	/// The last block to look in. Defaults to the best block.
+	to: Option<BlockNumber>,
+}
+
+/// The position of an event in the chain, to resume a query from.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct EventCursor {
+	/// The number of the block of the event.
+	block_number: BlockNumber,
+	/// The index of the event in the events of its block.
+	index: u32,
+}
+
+/// An event returned by a query.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// The number of the block of the event.
+	pub block_number: BlockNumber,
+	/// The hash of the block of the event.
+	pub block_hash: Hash,
+	/// The index of the event in the events of its block.
+	pub index: u32,
+	/// The index of the extrinsic that deposited the event, if any.
+	pub extrinsic: Option<u32>,
+	/// The SCALE encoded `RuntimeEvent`.
+	pub event: Bytes,
+	/// The topics of the event.
+	pub topics: Vec<Hash>,
+}
+
+/// A page of events returned by a query.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPage {
+	/// The events, in chain order.
+	pub events: Vec<Event>,
+	/// Where the next page starts, if there are more events.
+	pub next: Option<EventCursor>,
+}
+
+/// Event index RPC methods.
+#[rpc(server)]
+pub trait EventsApi {
+	/// Returns the events matching `query`, in chain order, starting from `cursor` and up to
+	/// `limit` of them (100 by default, 1000 at most).
+	///
+	/// The events are SCALE encoded; `next` in the result is the cursor of the next page.
+	#[method(name = "events_query")]
+	fn query(
+		&self,
+		query: EventQuery,
+		cursor: Option<EventCursor>,
+		limit: Option<u32>,
+	) -> RpcResult<EventPage>;
+}
+
+/// Provides RPC methods to query the event index.
+pub struct Events {
+	db: Arc<IndexDb>,
+}
+
+impl Events {
+	/// Create new `Events` with the given reference to the event index.
+	pub fn new(db: Arc<IndexDb>) -> Self {
+		Self { db }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The query is not valid.
+	InvalidQuery,
+	/// The index could not be read.
+	IndexError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::InvalidQuery => 1,
+			Error::IndexError => 2,
+		}
+	}
+}
+
+fn invalid_query(message: String) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::InvalidQuery.into(), message, None::<()>)).into()
+}
+
+fn index_error(e: String) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::IndexError.into(),
+		"Unable to query the event index.",
+		Some(e),
+	))
+	.into()
+}
+
+impl EventsApiServer for Events {
+	fn query(
+		&self,
+		query: EventQuery,
+		cursor: Option<EventCursor>,
+		limit: Option<u32>,
+	) -> RpcResult<EventPage> {
+		let EventQuery { pallet, variant, account, topic, from, to } = query;
+		let (pallet, variant) = events::event_indices(&pallet, &variant)
+			.ok_or_else(|| invalid_query(format!("Unknown event {}::{}", pallet, variant)))?;
+		let subject = match (account, topic) {
+			(Some(_), Some(_)) =>
+				return Err(invalid_query("Only one of account and topic can be set".into())),
+			(Some(account), None) => Some(account.into()),
+			(None, Some(topic)) => Some(topic.0),
+			(None, None) => None,
+		};
+		let filter = EventFilter {
+			pallet,
+			variant,
+			subject,
+			from: from.unwrap_or(0),
+			to: to.unwrap_or(BlockNumber::MAX),
+		};
+		let start = cursor.map(|cursor| (cursor.block_number, cursor.index));
+		let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+		let (found, next) =
+			events::query(&self.db, &filter, start, limit as usize).map_err(index_error)?;
+
+		let events = found
+			.into_iter()
+			.map(|found| {
+				let (block_number, index) = found.id;
+				Event {
+					block_number,
+					block_hash: found.block_hash,
+					index,
+					extrinsic: match found.record.phase {
+						Phase::ApplyExtrinsic(extrinsic) => Some(extrinsic),
+						_ => None,
+					},
+					event: found.record.event.encode().into(),
+					topics: found.record.topics,
+				}
+			})
+			.collect();
+		let next = next.map(|(block_number, index): EventId| EventCursor { block_number, index });
+
+		Ok(EventPage { events, next })
+	}
+}
@@ -0,0 +1,84 @@
+//! RPC interface resolving accounts to their on-chain identity.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::apis::IdentityApi as IdentityRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Identity RPC methods.
+#[rpc(server)]
+pub trait IdentityApi<BlockHash, AccountId> {
+	/// Returns the display name `account` registered with `pallet_identity`, if any.
+	///
+	/// Sub-accounts resolve to `<parent display name>/<sub-account name>`. Names that are not
+	/// valid UTF-8 are decoded lossily.
+	#[method(name = "identity_displayName")]
+	fn display_name(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<String>>;
+}
+
+/// Provides RPC methods to query identities.
+pub struct Identity<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Identity<C, B> {
+	/// Create new `Identity` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId> IdentityApiServer<<Block as BlockT>::Hash, AccountId>
+	for Identity<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: IdentityRuntimeApi<Block, AccountId>,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn display_name(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<String>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let name = api.display_name(at_hash, account).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to query display name.",
+				Some(e.to_string()),
+			))
+		})?;
+
+		Ok(name.map(|name| String::from_utf8_lossy(&name).into_owned()))
+	}
+}
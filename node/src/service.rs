@@ -1,8 +1,22 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use crate::{
+	authoring::AuthoringSwitch,
+	eth::{
+		new_frontier_partial, open_frontier_backend, spawn_frontier_tasks, EthConfiguration,
+		FrontierBackend, FrontierPartialComponents,
+	},
+	export::{spawn_exporter, ExportConfiguration},
+	indexer::{accounts::HistoryIndex, events::EventIndex, spawn_index, IndexerConfiguration},
+	pool::{
+		admission::{AdmissionPolicy, AdmissionPool},
+		spawn_pool_snapshots, PoolConfiguration,
+	},
+	proposer::{BlockPolicy, ProposerConfiguration, SelectingPool},
+};
+use node_template_runtime::{self, opaque::Block, RuntimeApi, TransactionConverter};
 use sc_client_api::BlockBackend;
-use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
+use sc_consensus_aura::{ImportQueueParams, StartAuraParams};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
@@ -33,7 +47,7 @@ impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
 
 pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
-type FullBackend = sc_service::TFullBackend<Block>;
+pub(crate) type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
 pub fn new_partial(
@@ -53,6 +67,7 @@ pub fn new_partial(
 				FullSelectChain,
 			>,
 			sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+			Arc<FrontierBackend>,
 			Option<Telemetry>,
 		),
 	>,
@@ -110,6 +125,8 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	let frontier_backend = open_frontier_backend(client.clone(), config)?;
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
 	let import_queue =
@@ -143,7 +160,7 @@ pub fn new_partial(
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (grandpa_block_import, grandpa_link, telemetry),
+		other: (grandpa_block_import, grandpa_link, frontier_backend, telemetry),
 	})
 }
 
@@ -155,7 +172,14 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	eth_config: EthConfiguration,
+	indexer_config: IndexerConfiguration,
+	export_config: ExportConfiguration,
+	pool_config: PoolConfiguration,
+	proposer_config: ProposerConfiguration,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -164,9 +188,28 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		mut keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (block_import, grandpa_link, mut telemetry),
+		other: (block_import, grandpa_link, frontier_backend, mut telemetry),
 	} = new_partial(&config)?;
 
+	let FrontierPartialComponents { filter_pool, fee_history_cache, fee_history_cache_limit } =
+		new_frontier_partial(&eth_config);
+
+	let admission_policy = match &pool_config.pool_admission_policy {
+		Some(path) => AdmissionPolicy::load(path).map_err(ServiceError::Other)?,
+		None => AdmissionPolicy::default(),
+	};
+	// RPC clients and peers submit transactions through the admission policy, while block
+	// authoring reads the inner pool directly.
+	let admission_pool = Arc::new(
+		AdmissionPool::new(
+			transaction_pool.clone(),
+			client.clone(),
+			admission_policy,
+			config.prometheus_registry(),
+		)
+		.map_err(|e| ServiceError::Other(e.to_string()))?,
+	);
+
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
 			Ok(k) => keystore_container.set_remote_keystore(k),
@@ -196,7 +239,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		sc_service::build_network(sc_service::BuildNetworkParams {
 			config: &config,
 			client: client.clone(),
-			transaction_pool: transaction_pool.clone(),
+			transaction_pool: admission_pool.clone(),
 			spawn_handle: task_manager.spawn_handle(),
 			import_queue,
 			block_announce_validator_builder: None,
@@ -219,14 +262,119 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	let overrides = crate::rpc::overrides_handle(client.clone());
+	let block_data_cache = Arc::new(fc_rpc::EthBlockDataCacheTask::new(
+		task_manager.spawn_handle(),
+		overrides.clone(),
+		eth_config.eth_log_block_cache,
+		eth_config.eth_statuses_cache,
+		prometheus_registry.clone(),
+	));
+
+	let event_index = if indexer_config.index_events {
+		Some(
+			spawn_index(&task_manager, &config, client.clone(), EventIndex)
+				.map_err(ServiceError::Other)?,
+		)
+	} else {
+		None
+	};
+	let history_index = if indexer_config.index_accounts {
+		Some(
+			spawn_index(&task_manager, &config, client.clone(), HistoryIndex)
+				.map_err(ServiceError::Other)?,
+		)
+	} else {
+		None
+	};
+
+	if let Some(sink) = export_config.export_sink {
+		spawn_exporter(
+			&task_manager,
+			&config,
+			client.clone(),
+			sink,
+			export_config.export_rotate_size,
+		)
+		.map_err(ServiceError::Other)?;
+	}
+
+	// Restored transactions are checked against the admission policy, like the ones of peers.
+	if pool_config.pool_snapshot {
+		spawn_pool_snapshots(
+			&task_manager,
+			&config,
+			client.clone(),
+			admission_pool.clone(),
+			Duration::from_secs(pool_config.pool_snapshot_interval),
+		)
+		.map_err(ServiceError::Other)?;
+	}
+
+	let authoring = AuthoringSwitch::default();
+	let shared_voter_state = SharedVoterState::empty();
+	let shared_authority_set = grandpa_link.shared_authority_set().clone();
+	let justification_stream = grandpa_link.justification_stream();
+	let finality_proof_provider = sc_finality_grandpa::FinalityProofProvider::new_for_service(
+		backend.clone(),
+		Some(shared_authority_set.clone()),
+	);
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
-		let pool = transaction_pool.clone();
-
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
-			crate::rpc::create_full(deps).map_err(Into::into)
+		let backend = backend.clone();
+		let pool = admission_pool.clone();
+		let graph = transaction_pool.pool().clone();
+		let network = network.clone();
+		let frontier_backend = frontier_backend.clone();
+		let overrides = overrides.clone();
+		let filter_pool = filter_pool.clone();
+		let fee_history_cache = fee_history_cache.clone();
+		let is_authority = role.is_authority();
+		let keystore = keystore_container.sync_keystore();
+		let authoring = authoring.clone();
+		let shared_voter_state = shared_voter_state.clone();
+
+		Box::new(move |deny_unsafe, subscription_task_executor| {
+			let eth = crate::rpc::EthDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				graph: graph.clone(),
+				converter: Some(TransactionConverter),
+				is_authority,
+				enable_dev_signer: eth_config.enable_dev_signer,
+				network: network.clone(),
+				frontier_backend: frontier_backend.clone(),
+				overrides: overrides.clone(),
+				block_data_cache: block_data_cache.clone(),
+				filter_pool: filter_pool.clone(),
+				max_past_logs: eth_config.max_past_logs,
+				fee_history_cache: fee_history_cache.clone(),
+				fee_history_cache_limit,
+				execute_gas_limit_multiplier: eth_config.execute_gas_limit_multiplier,
+			};
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				backend: backend.clone(),
+				pool: pool.clone(),
+				event_index: event_index.clone(),
+				history_index: history_index.clone(),
+				deny_unsafe,
+				admin: crate::rpc::AdminDeps {
+					keystore: keystore.clone(),
+					authoring: authoring.clone(),
+					shared_voter_state: shared_voter_state.clone(),
+				},
+				grandpa: crate::rpc::GrandpaDeps {
+					shared_voter_state: shared_voter_state.clone(),
+					shared_authority_set: shared_authority_set.clone(),
+					justification_stream: justification_stream.clone(),
+					subscription_executor: subscription_task_executor.clone(),
+					finality_provider: finality_proof_provider.clone(),
+				},
+				eth,
+			};
+			crate::rpc::create_full(deps, subscription_task_executor).map_err(Into::into)
 		})
 	};
 
@@ -235,20 +383,39 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		client: client.clone(),
 		keystore: keystore_container.sync_keystore(),
 		task_manager: &mut task_manager,
-		transaction_pool: transaction_pool.clone(),
+		transaction_pool: admission_pool,
 		rpc_builder: rpc_extensions_builder,
-		backend,
+		backend: backend.clone(),
 		system_rpc_tx,
 		tx_handler_controller,
 		config,
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	spawn_frontier_tasks(
+		&task_manager,
+		client.clone(),
+		backend,
+		frontier_backend,
+		filter_pool,
+		overrides,
+		fee_history_cache,
+		fee_history_cache_limit,
+	);
+
 	if role.is_authority() {
+		let block_policy = BlockPolicy::new(&proposer_config).map_err(ServiceError::Other)?;
+		let selecting_pool = SelectingPool::new(
+			transaction_pool,
+			client.clone(),
+			block_policy,
+			prometheus_registry.as_ref(),
+		)
+		.map_err(|e| ServiceError::Other(e.to_string()))?;
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
-			transaction_pool,
+			Arc::new(selecting_pool),
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
@@ -276,9 +443,9 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 				force_authoring,
 				backoff_authoring_blocks,
 				keystore: keystore_container.sync_keystore(),
-				sync_oracle: network.clone(),
+				sync_oracle: authoring.sync_oracle(network.clone()),
 				justification_sync_link: network.clone(),
-				block_proposal_slot_portion: SlotProportion::new(2f32 / 3f32),
+				block_proposal_slot_portion: proposer_config.slot_proportion(),
 				max_block_proposal_slot_portion: None,
 				telemetry: telemetry.as_ref().map(|x| x.handle()),
 				compatibility_mode: Default::default(),
@@ -322,7 +489,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			network,
 			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
-			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_state,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 		};
 
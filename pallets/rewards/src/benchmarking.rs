@@ -0,0 +1,27 @@
+//! Benchmarking setup for pallet-rewards
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Rewards;
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+
+benchmarks! {
+	set_inflation {
+		let inflation = Inflation::Decaying {
+			initial: Perbill::from_percent(10),
+			decay: Perbill::from_percent(10),
+			floor: Perbill::from_percent(2),
+		};
+	}: _(RawOrigin::Root, inflation)
+	verify {
+		assert_eq!(InflationSchedule::<T>::get(), inflation);
+	}
+
+	impl_benchmark_test_suite!(
+		Rewards,
+		crate::mock::new_test_ext(Default::default()),
+		crate::mock::Test
+	);
+}
@@ -0,0 +1,237 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Rewards Pallet
+//!
+//! Mints a reward to the author of every block, and to the stakers of every era.
+//!
+//! The reward is a share of the yearly [`Inflation`], spread evenly over the blocks of a year:
+//! at the start of every year (every `BlocksPerYear` blocks) the yearly rate is applied to the
+//! total issuance at that point, and divided by `BlocksPerYear`. The rate is either fixed or
+//! decays every year towards a floor.
+//!
+//! [`Config::StakersShare`] of every block reward isn't minted to the author but left to stakers:
+//! the runtime's era payout mints it at the end of the era, see [`Pallet::stakers_reward`]. The
+//! total inflation is the schedule's either way.
+//!
+//! The pallet is notified of block authors through [`pallet_authorship::EventHandler`], so the
+//! runtime must list it in `pallet_authorship::Config::EventHandler`.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::{Currency, Imbalance};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	Perbill, RuntimeDebug,
+};
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// How the yearly inflation rate evolves.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Inflation {
+	/// The same share of the total issuance is minted every year.
+	Fixed(Perbill),
+	/// The yearly rate starts at `initial` and loses `decay` of itself every year, until it
+	/// reaches `floor`.
+	Decaying { initial: Perbill, decay: Perbill, floor: Perbill },
+}
+
+impl Inflation {
+	/// The rate for the first year.
+	pub fn initial_rate(&self) -> Perbill {
+		match self {
+			Self::Fixed(rate) => *rate,
+			Self::Decaying { initial, .. } => *initial,
+		}
+	}
+
+	/// The rate for the year following a year at `rate`.
+	pub fn next_rate(&self, rate: Perbill) -> Perbill {
+		match self {
+			Self::Fixed(fixed) => *fixed,
+			Self::Decaying { decay, floor, .. } => (rate - *decay * rate).max(*floor),
+		}
+	}
+}
+
+impl Default for Inflation {
+	fn default() -> Self {
+		Self::Fixed(Perbill::zero())
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency rewards are minted in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The number of blocks in a year. The inflation rate is applied once per year.
+		#[pallet::constant]
+		type BlocksPerYear: Get<u32>;
+
+		/// The share of every block reward paid to stakers at the end of the era instead of to the
+		/// block author.
+		#[pallet::constant]
+		type StakersShare: Get<Perbill>;
+	}
+
+	/// The inflation schedule.
+	#[pallet::storage]
+	#[pallet::getter(fn inflation)]
+	pub type InflationSchedule<T> = StorageValue<_, Inflation, ValueQuery>;
+
+	/// The inflation rate of the current year.
+	#[pallet::storage]
+	#[pallet::getter(fn current_rate)]
+	pub type CurrentRate<T> = StorageValue<_, Perbill, ValueQuery>;
+
+	/// The amount minted to the author of each block in the current year.
+	#[pallet::storage]
+	#[pallet::getter(fn block_reward)]
+	pub type BlockReward<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		pub inflation: Inflation,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self { inflation: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Pallet::<T>::start_schedule(self.inflation);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A block author was rewarded. [author, amount]
+		Rewarded { author: T::AccountId, amount: BalanceOf<T> },
+		/// A new year started, with a new block reward. [rate, block_reward]
+		NewYear { rate: Perbill, block_reward: BalanceOf<T> },
+		/// The inflation schedule was replaced. [inflation]
+		InflationSet { inflation: Inflation },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// `note_author` runs from `pallet_authorship::on_initialize` and can't report its own
+			// weight: it reads the reward and writes the author's account and the total issuance.
+			let mut weight = T::DbWeight::get().reads_writes(2, 2);
+
+			let blocks_per_year: T::BlockNumber = T::BlocksPerYear::get().into();
+			if !now.is_zero() && !blocks_per_year.is_zero() && (now % blocks_per_year).is_zero() {
+				let rate = Self::inflation().next_rate(Self::current_rate());
+				let block_reward = Self::set_rate(rate);
+				Self::deposit_event(Event::NewYear { rate, block_reward });
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 2));
+			}
+
+			weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Replace the inflation schedule. The block reward is recomputed right away, from the
+		/// schedule's initial rate and the current total issuance.
+		#[pallet::call_index(0)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 3).ref_time())]
+		pub fn set_inflation(origin: OriginFor<T>, inflation: Inflation) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::start_schedule(inflation);
+
+			Self::deposit_event(Event::InflationSet { inflation });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn start_schedule(inflation: Inflation) {
+			InflationSchedule::<T>::put(inflation);
+			Self::set_rate(inflation.initial_rate());
+		}
+
+		/// Make `rate` the current rate and derive the block reward from it.
+		fn set_rate(rate: Perbill) -> BalanceOf<T> {
+			let block_reward = Self::reward_for(rate, T::Currency::total_issuance());
+			CurrentRate::<T>::put(rate);
+			BlockReward::<T>::put(block_reward);
+			block_reward
+		}
+
+		/// The per-block reward minting `rate` of `issuance` over a year.
+		pub fn reward_for(rate: Perbill, issuance: BalanceOf<T>) -> BalanceOf<T> {
+			let blocks_per_year = T::BlocksPerYear::get();
+			if blocks_per_year == 0 {
+				return Zero::zero()
+			}
+			rate.mul_floor(issuance) / BalanceOf::<T>::from(blocks_per_year)
+		}
+
+		/// The share of the block reward minted to the block author.
+		pub fn author_reward() -> BalanceOf<T> {
+			let block_reward = Self::block_reward();
+			block_reward.saturating_sub(T::StakersShare::get() * block_reward)
+		}
+
+		/// The share of the block reward of `blocks` blocks left to stakers, for the runtime's
+		/// era payout to mint.
+		pub fn stakers_reward(blocks: u32) -> BalanceOf<T> {
+			(T::StakersShare::get() * Self::block_reward()).saturating_mul(blocks.into())
+		}
+	}
+}
+
+impl<T: Config> pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Pallet<T> {
+	fn note_author(author: T::AccountId) {
+		let reward = Self::author_reward();
+		if reward.is_zero() {
+			return
+		}
+
+		// Dropping the imbalance is what raises the total issuance.
+		let minted = T::Currency::deposit_creating(&author, reward).peek();
+		if !minted.is_zero() {
+			Self::deposit_event(Event::Rewarded { author, amount: minted });
+		}
+	}
+
+	fn note_uncle(_author: T::AccountId, _age: T::BlockNumber) {}
+}
@@ -0,0 +1,132 @@
+use crate::{self as pallet_rewards, Inflation};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, FindAuthor, GenesisBuild, Hooks},
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	ConsensusEngineId, Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+/// The account authoring every block, unless a test changes [`BlockAuthor`].
+pub const AUTHOR: u64 = 11;
+/// The total issuance at genesis.
+pub const INITIAL_ISSUANCE: u64 = 1_000_000_000;
+/// Blocks per year in the mock runtime.
+pub const BLOCKS_PER_YEAR: u32 = 100;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Authorship: pallet_authorship,
+		Rewards: pallet_rewards,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static BlockAuthor: Option<u64> = Some(AUTHOR);
+	pub static StakersShare: Perbill = Perbill::from_percent(0);
+}
+
+/// Finds [`BlockAuthor`], whatever the digest.
+pub struct MockFindAuthor;
+impl FindAuthor<u64> for MockFindAuthor {
+	fn find_author<'a, I>(_digests: I) -> Option<u64>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		BlockAuthor::get()
+	}
+}
+
+impl pallet_authorship::Config for Test {
+	type FindAuthor = MockFindAuthor;
+	type UncleGenerations = ConstU64<0>;
+	type FilterUncle = ();
+	type EventHandler = Rewards;
+}
+
+impl pallet_rewards::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BlocksPerYear = ConstU32<BLOCKS_PER_YEAR>;
+	type StakersShare = StakersShare;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext(inflation: Inflation) -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, INITIAL_ISSUANCE / 2), (2, INITIAL_ISSUANCE / 2)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	GenesisBuild::<Test>::assimilate_storage(&pallet_rewards::GenesisConfig { inflation }, &mut t)
+		.unwrap();
+	t.into()
+}
+
+/// Run blocks up to and including `n`, in the order the runtime executes the pallets.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		if System::block_number() > 0 {
+			Authorship::on_finalize(System::block_number());
+			System::on_finalize(System::block_number());
+		}
+		System::set_block_number(System::block_number() + 1);
+		System::on_initialize(System::block_number());
+		Authorship::on_initialize(System::block_number());
+		Rewards::on_initialize(System::block_number());
+	}
+}
@@ -0,0 +1,130 @@
+use crate::{mock::*, Event, Inflation};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{DispatchError, Perbill};
+
+#[test]
+fn genesis_sets_the_block_reward() {
+	new_test_ext(Inflation::Fixed(Perbill::from_percent(10))).execute_with(|| {
+		assert_eq!(Rewards::current_rate(), Perbill::from_percent(10));
+		// 10% of the issuance, spread over a year.
+		assert_eq!(Rewards::block_reward(), INITIAL_ISSUANCE / 10 / BLOCKS_PER_YEAR as u64);
+	});
+}
+
+#[test]
+fn block_reward_is_minted_to_the_author() {
+	new_test_ext(Inflation::Fixed(Perbill::from_percent(10))).execute_with(|| {
+		let reward = Rewards::block_reward();
+
+		run_to_block(10);
+
+		assert_eq!(Balances::free_balance(AUTHOR), 10 * reward);
+		assert_eq!(Balances::total_issuance(), INITIAL_ISSUANCE + 10 * reward);
+		System::assert_last_event(Event::Rewarded { author: AUTHOR, amount: reward }.into());
+	});
+}
+
+#[test]
+fn stakers_share_is_left_out_of_the_author_reward() {
+	new_test_ext(Inflation::Fixed(Perbill::from_percent(10))).execute_with(|| {
+		StakersShare::set(Perbill::from_percent(40));
+		let reward = Rewards::block_reward();
+
+		run_to_block(10);
+
+		assert_eq!(Rewards::author_reward(), reward - reward * 2 / 5);
+		assert_eq!(Balances::free_balance(AUTHOR), 10 * Rewards::author_reward());
+		// The rest is the stakers' to mint: the schedule's inflation is split, not raised.
+		assert_eq!(Rewards::stakers_reward(10), 10 * (reward * 2 / 5));
+		assert_eq!(
+			Balances::total_issuance() + Rewards::stakers_reward(10),
+			INITIAL_ISSUANCE + 10 * reward,
+		);
+	});
+}
+
+#[test]
+fn issuance_compounds_at_the_fixed_yearly_rate() {
+	new_test_ext(Inflation::Fixed(Perbill::from_percent(10))).execute_with(|| {
+		let mut expected = INITIAL_ISSUANCE;
+		for year in 1..=5u64 {
+			run_to_block(year * BLOCKS_PER_YEAR as u64);
+			expected += expected / 10;
+			assert_eq!(Balances::total_issuance(), expected);
+			// The reward of the next year is taken from the grown issuance.
+			assert_eq!(Rewards::block_reward(), expected / 10 / BLOCKS_PER_YEAR as u64);
+		}
+		assert_eq!(Balances::total_issuance(), 1_610_510_000);
+	});
+}
+
+#[test]
+fn decaying_inflation_stops_at_the_floor() {
+	let inflation = Inflation::Decaying {
+		initial: Perbill::from_percent(10),
+		decay: Perbill::from_percent(50),
+		floor: Perbill::from_percent(2),
+	};
+	new_test_ext(inflation).execute_with(|| {
+		let rates = [
+			Perbill::from_percent(5),
+			Perbill::from_perthousand(25),
+			Perbill::from_percent(2),
+			Perbill::from_percent(2),
+		];
+		for (year, rate) in rates.into_iter().enumerate() {
+			let issuance = Balances::total_issuance();
+			let reward = Rewards::block_reward();
+
+			run_to_block((year as u64 + 1) * BLOCKS_PER_YEAR as u64);
+
+			// A whole year of rewards at the previous rate was minted...
+			assert_eq!(Balances::total_issuance(), issuance + reward * BLOCKS_PER_YEAR as u64);
+			// ...and the rate decayed for the next one.
+			assert_eq!(Rewards::current_rate(), rate);
+			System::assert_last_event(
+				Event::NewYear { rate, block_reward: Rewards::block_reward() }.into(),
+			);
+		}
+	});
+}
+
+#[test]
+fn nothing_is_minted_without_an_author() {
+	new_test_ext(Inflation::Fixed(Perbill::from_percent(10))).execute_with(|| {
+		BlockAuthor::set(None);
+
+		run_to_block(10);
+
+		assert_eq!(Balances::total_issuance(), INITIAL_ISSUANCE);
+	});
+}
+
+#[test]
+fn nothing_is_minted_without_inflation() {
+	new_test_ext(Inflation::default()).execute_with(|| {
+		run_to_block(3 * BLOCKS_PER_YEAR as u64);
+
+		assert_eq!(Balances::total_issuance(), INITIAL_ISSUANCE);
+		assert_eq!(Balances::free_balance(AUTHOR), 0);
+	});
+}
+
+#[test]
+fn set_inflation_requires_root() {
+	new_test_ext(Inflation::default()).execute_with(|| {
+		let inflation = Inflation::Fixed(Perbill::from_percent(5));
+		assert_noop!(
+			Rewards::set_inflation(RuntimeOrigin::signed(1), inflation),
+			DispatchError::BadOrigin
+		);
+
+		System::set_block_number(1);
+		assert_ok!(Rewards::set_inflation(RuntimeOrigin::root(), inflation));
+
+		assert_eq!(Rewards::inflation(), inflation);
+		assert_eq!(Rewards::current_rate(), Perbill::from_percent(5));
+		assert_eq!(Rewards::block_reward(), INITIAL_ISSUANCE / 20 / BLOCKS_PER_YEAR as u64);
+		System::assert_last_event(Event::InflationSet { inflation }.into());
+	});
+}
@@ -0,0 +1,51 @@
+//! Benchmarking setup for pallet-tasks
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Tasks;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const TASK: TaskName = [7; 32];
+
+fn remark<T: Config>() -> Box<CallOf<T>> {
+	Box::new(frame_system::Call::<T>::remark { remark: vec![] }.into())
+}
+
+benchmarks! {
+	schedule {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let when = frame_system::Pallet::<T>::block_number() + 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), TASK, when, Some((1u32.into(), 10)), remark::<T>())
+	verify {
+		assert_eq!(Tasks::<T>::tasks(&caller).len(), 1);
+	}
+
+	cancel {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let when = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		Tasks::<T>::schedule(
+			RawOrigin::Signed(caller.clone()).into(),
+			TASK,
+			when,
+			None,
+			remark::<T>(),
+		)?;
+		assert_eq!(T::Currency::reserved_balance(&caller), T::TaskDeposit::get());
+	}: _(RawOrigin::Signed(caller.clone()), TASK)
+	verify {
+		assert!(Tasks::<T>::tasks(&caller).is_empty());
+	}
+
+	run {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller), TASK, remark::<T>())
+
+	impl_benchmark_test_suite!(Tasks, crate::mock::new_test_ext(), crate::mock::Test);
+}
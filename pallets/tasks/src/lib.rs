@@ -0,0 +1,252 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Tasks Pallet
+//!
+//! Lets signed accounts schedule calls, one-off or periodic, through the runtime's scheduler.
+//!
+//! The scheduler dispatches scheduled calls for free, so this pallet makes signed accounts pay
+//! for what they schedule itself:
+//! - every run of a task withdraws the fee of its call's weight, following
+//!   [`Config::WeightToFee`], from its owner, and runs the owner can't pay for are skipped;
+//! - every task reserves [`Config::TaskDeposit`] from its owner until the owner cancels it;
+//! - an account can have at most [`Config::MaxTasksPerAccount`] tasks;
+//! - a task's call can weigh at most [`Config::MaxTaskWeight`].
+//!
+//! Tasks are scheduled as [`Call::run`], which charges the fee and dispatches the task's call
+//! with the signed origin of the account that scheduled it, at the lowest priority. Tasks are
+//! named by their owner, and the names of different owners never clash. Once a task ran for the
+//! last time, cancelling it only returns its deposit.
+//!
+//! The scheduler itself should only let privileged origins schedule calls, or this pallet's
+//! limits can be bypassed.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
+
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::{
+		schedule::{self, v3::Named, DispatchTime},
+		Currency, ExistenceRequirement, OnUnbalanced, ReservableCurrency, StorePreimage,
+		WithdrawReasons,
+	},
+	weights::WeightToFee,
+	Hashable,
+};
+use sp_runtime::traits::Dispatchable;
+use sp_std::prelude::*;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+type CallOf<T> = <T as Config>::RuntimeCall;
+
+/// The name of a task, unique among the tasks of its owner.
+pub type TaskName = schedule::v3::TaskName;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The calls tasks dispatch.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<Call<Self>>
+			+ From<frame_system::Call<Self>>;
+
+		/// The origins the scheduler dispatches calls with.
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+		/// The scheduler running the tasks.
+		type Scheduler: Named<Self::BlockNumber, CallOf<Self>, Self::PalletsOrigin>;
+
+		/// Stores the calls too large to be scheduled inline.
+		type Preimages: StorePreimage;
+
+		/// The currency deposits are reserved and fees paid in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Converts the weight of a task's call to the fee of every run.
+		type WeightToFee: WeightToFee<Balance = BalanceOf<Self>>;
+
+		/// Handles the fees of runs.
+		type OnTaskFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The deposit reserved for every task.
+		#[pallet::constant]
+		type TaskDeposit: Get<BalanceOf<Self>>;
+
+		/// The maximum number of tasks an account can have.
+		#[pallet::constant]
+		type MaxTasksPerAccount: Get<u32>;
+
+		/// The maximum weight of the call of a task.
+		#[pallet::constant]
+		type MaxTaskWeight: Get<Weight>;
+
+		/// Type representing the weight of this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The tasks of every account, with the deposit reserved for each of them.
+	#[pallet::storage]
+	#[pallet::getter(fn tasks)]
+	pub type Tasks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(TaskName, BalanceOf<T>), T::MaxTasksPerAccount>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A task was scheduled. [owner, task]
+		Scheduled { owner: T::AccountId, task: TaskName },
+		/// A task was cancelled and its deposit returned. [owner, task]
+		Cancelled { owner: T::AccountId, task: TaskName },
+		/// A task ran, its fee paid. [owner, task, fee, result]
+		Ran { owner: T::AccountId, task: TaskName, fee: BalanceOf<T>, result: DispatchResult },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account already has a task with this name.
+		DuplicateTask,
+		/// The account has no task with this name.
+		UnknownTask,
+		/// The account already has as many tasks as it may.
+		TooManyTasks,
+		/// The call weighs more than a task may.
+		TaskTooHeavy,
+		/// The owner of the task can't pay for the run.
+		CannotPayFee,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Schedule `call` to be dispatched from the caller's origin at block `when`, and then
+		/// every `maybe_periodic.0` blocks, `maybe_periodic.1` times in total. Every run charges
+		/// the fee of `call`'s weight.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::schedule())]
+		pub fn schedule(
+			origin: OriginFor<T>,
+			task: TaskName,
+			when: T::BlockNumber,
+			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+			call: Box<CallOf<T>>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(
+				call.get_dispatch_info().weight.all_lte(T::MaxTaskWeight::get()),
+				Error::<T>::TaskTooHeavy
+			);
+
+			Tasks::<T>::try_mutate(&owner, |tasks| -> DispatchResult {
+				ensure!(tasks.iter().all(|(name, _)| *name != task), Error::<T>::DuplicateTask);
+
+				let deposit = T::TaskDeposit::get();
+				tasks.try_push((task, deposit)).map_err(|_| Error::<T>::TooManyTasks)?;
+				T::Currency::reserve(&owner, deposit)?;
+
+				T::Scheduler::schedule_named(
+					Self::scheduler_id(&owner, &task),
+					DispatchTime::At(when),
+					maybe_periodic,
+					schedule::LOWEST_PRIORITY,
+					frame_system::RawOrigin::Signed(owner.clone()).into(),
+					T::Preimages::bound(Call::<T>::run { task, call }.into())?,
+				)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Scheduled { owner, task });
+			Ok(())
+		}
+
+		/// Cancel a task of the caller, if it is still scheduled, and return its deposit.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::cancel())]
+		pub fn cancel(origin: OriginFor<T>, task: TaskName) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			Tasks::<T>::try_mutate_exists(&owner, |maybe_tasks| -> DispatchResult {
+				let tasks = maybe_tasks.as_mut().ok_or(Error::<T>::UnknownTask)?;
+				let position = tasks
+					.iter()
+					.position(|(name, _)| *name == task)
+					.ok_or(Error::<T>::UnknownTask)?;
+				let (_, deposit) = tasks.remove(position);
+				if tasks.is_empty() {
+					*maybe_tasks = None;
+				}
+
+				// Tasks that ran for the last time are already gone from the scheduler.
+				let _ = T::Scheduler::cancel_named(Self::scheduler_id(&owner, &task));
+				T::Currency::unreserve(&owner, deposit);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Cancelled { owner, task });
+			Ok(())
+		}
+
+		/// Pay the fee of `call` and dispatch it from the caller's origin, as a run of the task
+		/// `task`. Tasks are scheduled as this call; dispatching it directly only pays for `call`
+		/// on top of the transaction fee.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::run().saturating_add(call.get_dispatch_info().weight))]
+		pub fn run(origin: OriginFor<T>, task: TaskName, call: Box<CallOf<T>>) -> DispatchResult {
+			let owner = ensure_signed(origin.clone())?;
+
+			let fee = T::WeightToFee::weight_to_fee(&call.get_dispatch_info().weight);
+			let paid = T::Currency::withdraw(
+				&owner,
+				fee,
+				WithdrawReasons::FEE,
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|_| Error::<T>::CannotPayFee)?;
+			T::OnTaskFee::on_unbalanced(paid);
+
+			let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+			Self::deposit_event(Event::Ran { owner, task, fee, result });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The name the scheduler knows the task `task` of `owner` by.
+		pub fn scheduler_id(owner: &T::AccountId, task: &TaskName) -> TaskName {
+			(b"tasks", owner, task).blake2_256()
+		}
+	}
+}
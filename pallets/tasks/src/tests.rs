@@ -0,0 +1,156 @@
+use crate::{mock::*, Error, Event, TaskName};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::GetDispatchInfo,
+	weights::{Weight, WeightToFee},
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const TASK: TaskName = [7; 32];
+
+fn remark(remark: &[u8]) -> Box<RuntimeCall> {
+	Box::new(frame_system::Call::remark_with_event { remark: remark.to_vec() }.into())
+}
+
+/// How many times `sender` remarked `remark`.
+fn remarks(sender: u64, remark: &[u8]) -> usize {
+	let hash: H256 = BlakeTwo256::hash(remark);
+	let event: RuntimeEvent = frame_system::Event::Remarked { sender, hash }.into();
+	System::events().into_iter().filter(|record| record.event == event).count()
+}
+
+#[test]
+fn tasks_run_with_the_origin_of_their_owner() {
+	new_test_ext().execute_with(|| {
+		// Remark three times, every three blocks from block 2.
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, Some((3, 3)), remark(b"hi")));
+		System::assert_last_event(Event::Scheduled { owner: 1, task: TASK }.into());
+		assert_eq!(Balances::reserved_balance(1), TASK_DEPOSIT);
+
+		run_to_block(10);
+		assert_eq!(remarks(1, b"hi"), 3);
+
+		// The task is over, cancelling it only returns the deposit.
+		assert_ok!(Tasks::cancel(RuntimeOrigin::signed(1), TASK));
+		System::assert_last_event(Event::Cancelled { owner: 1, task: TASK }.into());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(Tasks::tasks(1).is_empty());
+	});
+}
+
+#[test]
+fn every_run_is_paid_by_the_owner() {
+	new_test_ext().execute_with(|| {
+		let fee = MicrosToFee::weight_to_fee(&remark(b"hi").get_dispatch_info().weight);
+		assert!(fee > 0);
+		let issuance = Balances::total_issuance();
+
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, Some((3, 3)), remark(b"hi")));
+		run_to_block(10);
+
+		assert_eq!(remarks(1, b"hi"), 3);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - TASK_DEPOSIT - 3 * fee);
+		// The mock burns the fees.
+		assert_eq!(Balances::total_issuance(), issuance - 3 * fee);
+		System::assert_has_event(Event::Ran { owner: 1, task: TASK, fee, result: Ok(()) }.into());
+	});
+}
+
+#[test]
+fn runs_the_owner_cannot_pay_for_are_skipped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, Some((2, 2)), remark(b"hi")));
+		// Leave 1 only the existential deposit.
+		assert_ok!(Balances::transfer(
+			RuntimeOrigin::signed(1),
+			2,
+			INITIAL_BALANCE - TASK_DEPOSIT - 1
+		));
+
+		run_to_block(4);
+		assert_eq!(remarks(1, b"hi"), 0);
+		assert_eq!(Balances::free_balance(1), 1);
+		assert_noop!(
+			Tasks::run(RuntimeOrigin::signed(1), TASK, remark(b"hi")),
+			Error::<Test>::CannotPayFee
+		);
+	});
+}
+
+#[test]
+fn cancelled_tasks_stop_running() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, Some((2, 9)), remark(b"hi")));
+
+		run_to_block(4);
+		assert_ok!(Tasks::cancel(RuntimeOrigin::signed(1), TASK));
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		run_to_block(20);
+		assert_eq!(remarks(1, b"hi"), 2);
+	});
+}
+
+#[test]
+fn task_names_are_per_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, None, remark(b"one")));
+		assert_noop!(
+			Tasks::schedule(RuntimeOrigin::signed(1), TASK, 3, None, remark(b"one")),
+			Error::<Test>::DuplicateTask
+		);
+
+		// Another account may use the same name, and cancelling its task leaves the other one.
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(2), TASK, 2, None, remark(b"two")));
+		assert_ok!(Tasks::cancel(RuntimeOrigin::signed(2), TASK));
+		assert_noop!(Tasks::cancel(RuntimeOrigin::signed(2), TASK), Error::<Test>::UnknownTask);
+
+		run_to_block(2);
+		assert_eq!(remarks(1, b"one"), 1);
+		assert_eq!(remarks(2, b"two"), 0);
+	});
+}
+
+#[test]
+fn tasks_are_limited_per_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), [1; 32], 2, None, remark(b"hi")));
+		assert_ok!(Tasks::schedule(RuntimeOrigin::signed(1), [2; 32], 2, None, remark(b"hi")));
+		assert_noop!(
+			Tasks::schedule(RuntimeOrigin::signed(1), [3; 32], 2, None, remark(b"hi")),
+			Error::<Test>::TooManyTasks
+		);
+		assert_eq!(Balances::reserved_balance(1), 2 * TASK_DEPOSIT);
+
+		// Every task needs its deposit.
+		assert_ok!(Balances::transfer(RuntimeOrigin::signed(2), 1, INITIAL_BALANCE - 1));
+		assert_noop!(
+			Tasks::schedule(RuntimeOrigin::signed(2), [1; 32], 2, None, remark(b"hi")),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn heavy_calls_are_rejected() {
+	new_test_ext().execute_with(|| {
+		MaxTaskWeight::set(Weight::zero());
+		assert_noop!(
+			Tasks::schedule(RuntimeOrigin::signed(1), TASK, 2, None, remark(b"hi")),
+			Error::<Test>::TaskTooHeavy
+		);
+	});
+}
+
+#[test]
+fn tasks_are_not_scheduled_in_the_past() {
+	new_test_ext().execute_with(|| {
+		run_to_block(5);
+		assert_noop!(
+			Tasks::schedule(RuntimeOrigin::signed(1), TASK, 5, None, remark(b"hi")),
+			pallet_scheduler::Error::<Test>::TargetBlockNumberInPast
+		);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
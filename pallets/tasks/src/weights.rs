@@ -0,0 +1,98 @@
+//! Weights for pallet_tasks
+//!
+//! Execution times were not measured against this runtime yet: they are placeholders until this
+//! file is regenerated with the command below, on reference hardware.
+
+// Regenerate with:
+// ./target/release/node-template
+// benchmark
+// pallet
+// --chain
+// dev
+// --pallet
+// pallet_tasks
+// --extrinsic
+// *
+// --steps=50
+// --repeat=20
+// --execution=wasm
+// --wasm-execution=compiled
+// --output
+// pallets/tasks/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_tasks.
+pub trait WeightInfo {
+	fn schedule() -> Weight;
+	fn cancel() -> Weight;
+	fn run() -> Weight;
+}
+
+/// Weights for pallet_tasks using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Tasks Tasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	fn schedule() -> Weight {
+		// Minimum execution time: 60_000_000 picoseconds.
+		Weight::from_parts(62_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: Tasks Tasks (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn cancel() -> Weight {
+		// Minimum execution time: 55_000_000 picoseconds.
+		Weight::from_parts(57_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: System Account (r:1 w:1)
+	fn run() -> Weight {
+		// Minimum execution time: 25_000_000 picoseconds.
+		Weight::from_parts(26_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Tasks Tasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	fn schedule() -> Weight {
+		// Minimum execution time: 60_000_000 picoseconds.
+		Weight::from_parts(62_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: Tasks Tasks (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn cancel() -> Weight {
+		// Minimum execution time: 55_000_000 picoseconds.
+		Weight::from_parts(57_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: System Account (r:1 w:1)
+	fn run() -> Weight {
+		// Minimum execution time: 25_000_000 picoseconds.
+		Weight::from_parts(26_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -1,5 +1,5 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Error, Event, GenesisConfig};
+use frame_support::{assert_noop, assert_ok, traits::GenesisBuild};
 
 #[test]
 fn it_works_for_default_value() {
@@ -25,3 +25,17 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn genesis_sets_the_values_of_accounts() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> { somethings: vec![(1, 10), (2, 20)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(TemplateModule::something_of(1), Some(10));
+		assert_eq!(TemplateModule::something_of(2), Some(20));
+		assert_eq!(TemplateModule::something_of(3), None);
+	});
+}
@@ -0,0 +1,64 @@
+//! Runtime APIs declared by this runtime, for the node's RPCs and other off-chain tooling.
+
+use codec::{Codec, Decode, Encode};
+use frame_support::weights::Weight;
+use frame_system::RawOrigin;
+use sp_runtime::{
+	traits::Block as BlockT, transaction_validity::TransactionValidityError, DispatchResult,
+	RuntimeDebug,
+};
+use sp_std::vec::Vec;
+
+/// What dispatching a call or applying an extrinsic did, as reported by [`DryRunApi`].
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct DryRunEffects<RuntimeEvent, Balance> {
+	/// The outcome of the dispatch.
+	pub result: DispatchResult,
+	/// The events deposited by the dispatch.
+	pub events: Vec<RuntimeEvent>,
+	/// The weight the dispatch consumed, after refunds.
+	pub actual_weight: Weight,
+	/// The fee charged for the dispatch, tip included.
+	pub fee: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Resolves accounts to the names they registered with `pallet_identity`.
+	pub trait IdentityApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// The display name of `who`, as raw bytes.
+		///
+		/// Sub-accounts resolve to `<parent display name>/<sub-account name>`. Accounts without an
+		/// identity, or whose display name isn't stored as raw data, resolve to `None`.
+		fn display_name(who: AccountId) -> Option<Vec<u8>>;
+	}
+
+	/// Previews what calls and extrinsics would do, so that wallets can show it before asking
+	/// users to sign.
+	///
+	/// The functions are meant to be called on top of `Core::initialize_block` for a child of the
+	/// block whose state is used, the way a block builder would, and their changes to be thrown
+	/// away. Events deposited before the dry run, e.g. by `on_initialize` hooks, are cleared.
+	pub trait DryRunApi<RuntimeCall, RuntimeEvent, AccountId, Balance>
+	where
+		RuntimeCall: Codec,
+		RuntimeEvent: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// Dispatch `call` from `origin`, bypassing the transaction checks: nothing verifies or
+		/// bumps the signer's nonce, and no fee is withdrawn. The reported fee is what the call
+		/// would cost as a transaction signed with an sr25519 key, without tip.
+		fn dry_run_call(origin: RawOrigin<AccountId>, call: RuntimeCall)
+			-> DryRunEffects<RuntimeEvent, Balance>;
+
+		/// Apply `extrinsic` like a block builder would, with all its checks and fee payment.
+		///
+		/// The reported fee is the one paid through `pallet_transaction_payment`, so it is zero for
+		/// Ethereum transactions, which pay for gas in the EVM.
+		fn dry_run_extrinsic(extrinsic: <Block as BlockT>::Extrinsic)
+			-> Result<DryRunEffects<RuntimeEvent, Balance>, TransactionValidityError>;
+	}
+}
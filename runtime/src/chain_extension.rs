@@ -0,0 +1,69 @@
+//! A chain extension that lets smart contracts reach the template pallet.
+//!
+//! Contracts call into it with `seal_call_chain_extension`, passing one of the [`FuncId`]s below.
+//! See <https://use.ink/macros-attributes/chain-extension> for the ink! side of the bridge.
+
+use crate::{Runtime, RuntimeOrigin, TemplateModule};
+use codec::Encode;
+use frame_support::{dispatch::GetDispatchInfo, traits::Get};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig,
+};
+use sp_core::crypto::UncheckedFrom;
+use sp_runtime::DispatchError;
+
+/// The functions exposed by [`TemplateExtension`].
+enum FuncId {
+	/// Read `pallet_template::Something`. Returns the SCALE encoded `Option<u32>`.
+	GetSomething,
+	/// Dispatch `pallet_template::do_something` on behalf of the calling contract. Takes the
+	/// SCALE encoded `u32` to store.
+	DoSomething,
+}
+
+impl TryFrom<u16> for FuncId {
+	type Error = DispatchError;
+
+	fn try_from(func_id: u16) -> Result<Self, Self::Error> {
+		match func_id {
+			1 => Ok(Self::GetSomething),
+			2 => Ok(Self::DoSomething),
+			_ => Err(DispatchError::Other("Unimplemented func_id")),
+		}
+	}
+}
+
+/// Chain extension giving contracts read and write access to `pallet_template` storage.
+#[derive(Default)]
+pub struct TemplateExtension;
+
+impl ChainExtension<Runtime> for TemplateExtension {
+	fn call<E>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = Runtime>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let func_id = FuncId::try_from(env.func_id())?;
+		let mut env = env.buf_in_buf_out();
+
+		match func_id {
+			FuncId::GetSomething => {
+				env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+				let something = pallet_template::Something::<Runtime>::get();
+				env.write(&something.encode(), false, None)?;
+			},
+			FuncId::DoSomething => {
+				let something: u32 = env.read_as()?;
+				// Charge exactly what the extrinsic would, so contracts can't write for less.
+				let call = pallet_template::Call::<Runtime>::do_something { something };
+				env.charge_weight(call.get_dispatch_info().weight)?;
+
+				// The contract itself is the account storing the value.
+				let who = env.ext().address().clone();
+				TemplateModule::do_something(RuntimeOrigin::signed(who), something)?;
+			},
+		}
+
+		Ok(RetVal::Converging(0))
+	}
+}
@@ -0,0 +1,74 @@
+//! Implementations of FRAME traits specific to this runtime.
+
+use crate::{
+	AccountId, Authorship, Balance, Balances, FeesToTreasury, OriginCaller, Rewards, Treasury,
+	MILLISECS_PER_BLOCK,
+};
+use frame_support::traits::{Currency, Get, Imbalance, OnUnbalanced, PrivilegeCmp};
+use sp_runtime::{traits::SaturatedConversion, PerThing};
+use sp_std::cmp::Ordering;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Credits the author of the current block. Without an author, the amount is burned.
+pub struct Author;
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		if let Some(author) = Authorship::author() {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees between the treasury and the block author, following
+/// [`FeesToTreasury`]. Tips all go to the author.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let to_treasury = FeesToTreasury::get();
+			let (treasury, mut author) =
+				fees.ration(to_treasury.deconstruct(), to_treasury.left_from_one().deconstruct());
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut author);
+			}
+
+			Treasury::on_unbalanced(treasury);
+			Author::on_unbalanced(author);
+		}
+	}
+}
+
+/// Pays stakers, at the end of every era, the share of the block rewards of the era that
+/// `pallet_rewards` leaves to them, counting the blocks of the era from its duration.
+pub struct StakersPayout;
+impl pallet_staking::EraPayout<Balance> for StakersPayout {
+	fn era_payout(
+		_total_staked: Balance,
+		_total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		let blocks = (era_duration_millis / MILLISECS_PER_BLOCK).saturated_into();
+		(Rewards::stakers_reward(blocks), 0)
+	}
+}
+
+/// Orders origins for `pallet_scheduler`: root outranks every origin, any other origin only
+/// compares equal to itself.
+///
+/// This lets root cancel or reschedule any task, including the ones signed accounts schedule
+/// through `pallet_tasks`.
+pub struct RootOutranksAll;
+impl PrivilegeCmp<OriginCaller> for RootOutranksAll {
+	fn cmp_privilege(left: &OriginCaller, right: &OriginCaller) -> Option<Ordering> {
+		if left == right {
+			return Some(Ordering::Equal)
+		}
+
+		match (left, right) {
+			(OriginCaller::system(frame_system::RawOrigin::Root), _) => Some(Ordering::Greater),
+			(_, OriginCaller::system(frame_system::RawOrigin::Root)) => Some(Ordering::Less),
+			_ => None,
+		}
+	}
+}
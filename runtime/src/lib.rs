@@ -6,30 +6,43 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use fp_rpc::TransactionStatus;
+use frame_election_provider_support::{onchain, SequentialPhragmen, VoteWeight};
+use frame_system::{EnsureRoot, EnsureRootWithSuccess};
+use pallet_ethereum::{Call::transact, Transaction as EthereumTransaction};
+use pallet_evm::{
+	Account as EVMAccount, EnsureAddressTruncated, FeeCalculator, HashedAddressMapping, Runner,
+};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{crypto::KeyTypeId, OpaqueMetadata, H160, H256, U256};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		BlakeTwo256, Block as BlockT, ConvertInto, DispatchInfoOf, Dispatchable,
+		IdentifyAccount, NumberFor, One, OpaqueKeys, PostDispatchInfoOf, UniqueSaturatedInto,
+		Verify,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	transaction_validity::{TransactionSource, TransactionValidity, TransactionValidityError},
+	ApplyExtrinsicResult, ConsensusEngineId, MultiSignature, RuntimeDebug,
 };
-use sp_std::prelude::*;
+use sp_std::{marker::PhantomData, prelude::*};
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 // A few exports that help ease life for downstream crates.
 pub use frame_support::{
-	construct_runtime, parameter_types,
+	construct_runtime,
+	dispatch::DispatchClass,
+	parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, FindAuthor, Get, InstanceFilter,
+		KeyOwnerProofSystem, Nothing, OnFinalize, Randomness, StorageInfo, U128CurrencyToVote,
 	},
 	weights::{
 		constants::{
@@ -37,10 +50,11 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_staking::StakerStatus;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -50,6 +64,26 @@ pub use sp_runtime::{Perbill, Permill};
 /// Import the template pallet.
 pub use pallet_template;
 
+pub use pallet_rewards::Inflation;
+
+/// Chain extension exposing runtime pallets to smart contracts.
+mod chain_extension;
+use chain_extension::TemplateExtension;
+
+/// EVM precompiles, including one exposing the template pallet.
+mod precompiles;
+use precompiles::FrontierPrecompiles;
+
+/// Bag thresholds for the staking voter list.
+mod voter_bags;
+
+/// Runtime APIs specific to this runtime.
+pub mod apis;
+
+/// Fee routing, and other trait implementations specific to this runtime.
+mod impls;
+use impls::{DealWithFees, RootOutranksAll, StakersPayout};
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -66,6 +100,10 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
+/// The short index an account can claim with `pallet_indices`, usable in place of its
+/// `AccountId` in an `Address`.
+pub type AccountIndex = u32;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -137,18 +175,26 @@ pub fn native_version() -> NativeVersion {
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 
+/// The maximum size, in bytes, of the storage proof of a block: the proof-size dimension of
+/// `BlockWeights`. This bounds what a light client has to download to verify a block.
+pub const MAX_POV_SIZE: u64 = 5 * 1024 * 1024;
+
+/// The maximum number of Aura and GRANDPA authorities, and so of validators staking can elect.
+pub const MAX_AUTHORITIES: u32 = 32;
+
 parameter_types! {
 	pub const BlockHashCount: BlockNumber = 2400;
 	pub const Version: RuntimeVersion = VERSION;
-	/// We allow for 2 seconds of compute with a 6 second average block time.
+	/// We allow for 2 seconds of compute and `MAX_POV_SIZE` of proof with a 6 second average
+	/// block time.
 	pub BlockWeights: frame_system::limits::BlockWeights =
 		frame_system::limits::BlockWeights::with_sensible_defaults(
-			Weight::from_parts(2u64 * WEIGHT_REF_TIME_PER_SECOND, u64::MAX),
+			Weight::from_parts(2u64 * WEIGHT_REF_TIME_PER_SECOND, MAX_POV_SIZE),
 			NORMAL_DISPATCH_RATIO,
 		);
 	pub BlockLength: frame_system::limits::BlockLength = frame_system::limits::BlockLength
 		::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	pub const SS58Prefix: u8 = 42;
+	pub const SS58Prefix: u16 = SS58_FORMAT;
 }
 
 // Configure FRAME pallets to include in runtime.
@@ -165,7 +211,9 @@ impl frame_system::Config for Runtime {
 	/// The aggregated dispatch type that is available for extrinsics.
 	type RuntimeCall = RuntimeCall;
 	/// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-	type Lookup = AccountIdLookup<AccountId, ()>;
+	///
+	/// Accounts may be addressed by their `AccountId` or by their `AccountIndex`.
+	type Lookup = Indices;
 	/// The index type for storing how many extrinsics an account has signed.
 	type Index = Index;
 	/// The index type for blocks.
@@ -209,14 +257,20 @@ impl pallet_randomness_collective_flip::Config for Runtime {}
 
 impl pallet_aura::Config for Runtime {
 	type AuthorityId = AuraId;
-	type DisabledValidators = ();
-	type MaxAuthorities = ConstU32<32>;
+	type DisabledValidators = Session;
+	type MaxAuthorities = ConstU32<MAX_AUTHORITIES>;
+}
+
+parameter_types! {
+	// Equivocation reports stay valid for as long as the offender could still be slashed.
+	pub ReportLongevity: u64 =
+		BondingDuration::get() as u64 * SessionsPerEra::get() as u64 * Period::get() as u64;
 }
 
 impl pallet_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 
-	type KeyOwnerProofSystem = ();
+	type KeyOwnerProofSystem = Historical;
 
 	type KeyOwnerProof =
 		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -226,10 +280,15 @@ impl pallet_grandpa::Config for Runtime {
 		GrandpaId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation = ();
+	/// Equivocations are reported to `pallet_offences`, which slashes through staking.
+	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		Offences,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
-	type MaxAuthorities = ConstU32<32>;
+	type MaxAuthorities = ConstU32<MAX_AUTHORITIES>;
 }
 
 impl pallet_timestamp::Config for Runtime {
@@ -243,7 +302,28 @@ impl pallet_timestamp::Config for Runtime {
 /// Existential deposit.
 pub const EXISTENTIAL_DEPOSIT: u128 = 500;
 
+/// The symbol of the native token, as wallets show it.
+pub const TOKEN_SYMBOL: &str = "UNIT";
+/// The number of decimals wallets show balances with: a `UNIT` is `10^TOKEN_DECIMALS` of the
+/// smallest indivisible balance unit.
+pub const TOKEN_DECIMALS: u8 = 12;
+/// The SS58 format of the addresses of the chain, the generic Substrate one until the chain
+/// registers its own.
+pub const SS58_FORMAT: u16 = 42;
+
+// Currency units, in the smallest indivisible balance unit.
+pub const UNIT: Balance = 10u128.pow(TOKEN_DECIMALS as u32);
+pub const MILLIUNIT: Balance = UNIT / 1_000;
+pub const MICROUNIT: Balance = UNIT / 1_000_000;
+
+/// The deposit required to store `items` storage items occupying `bytes` bytes.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	items as Balance * 15 * MILLIUNIT + (bytes as Balance) * 6 * MICROUNIT
+}
+
 impl pallet_balances::Config for Runtime {
+	/// Locks are per lock identifier, not per schedule: vesting holds a single lock however many
+	/// schedules an account has, so this only needs to cover the pallets that lock funds.
 	type MaxLocks = ConstU32<50>;
 	type MaxReserves = ();
 	type ReserveIdentifier = [u8; 8];
@@ -251,19 +331,36 @@ impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type RuntimeEvent = RuntimeEvent;
-	type DustRemoval = ();
+	/// Dust left by reaped accounts goes to the treasury. Use `()` to burn it instead.
+	type DustRemoval = Treasury;
 	type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	// Indices are a scarce, 32-bit namespace: claiming one has to cost something.
+	pub const IndexDeposit: Balance = UNIT;
+}
+
+impl pallet_indices::Config for Runtime {
+	type AccountIndex = AccountIndex;
+	type Currency = Balances;
+	type Deposit = IndexDeposit;
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_indices::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub FeeMultiplier: Multiplier = Multiplier::one();
+	/// The share of transaction fees paid into the treasury, the rest going to the block author.
+	/// Kept in storage so that root can change it with `system::set_storage`.
+	pub storage FeesToTreasury: Perbill = Perbill::from_percent(80);
 }
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = CurrencyAdapter<Balances, DealWithFees>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
 	type LengthToFee = IdentityFee<Balance>;
@@ -278,6 +375,568 @@ impl pallet_sudo::Config for Runtime {
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const BlocksPerYear: u32 = 365 * DAYS + DAYS / 4;
+	// Half of every block reward is paid out to the stakers of the era.
+	pub const StakersShare: Perbill = Perbill::from_percent(50);
+}
+
+/// Mints the block reward to block authors and, through the era payout of staking, to stakers,
+/// following the inflation schedule set in genesis.
+impl pallet_rewards::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BlocksPerYear = BlocksPerYear;
+	type StakersShare = StakersShare;
+}
+
+parameter_types! {
+	pub const MinVestedTransfer: Balance = UNIT;
+}
+
+impl pallet_vesting::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
+	// `VestingInfo` encode length is 36bytes. 28 schedules gets encoded as 1009 bytes, which is the
+	// highest number of schedules that encodes less than 2^10.
+	const MAX_VESTING_SCHEDULES: u32 = 28;
+}
+
+impl pallet_utility::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const DepositBase: Balance = deposit(1, 88);
+	// Additional storage item size of 32 bytes.
+	pub const DepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
+	type MaxSignatories = ConstU32<100>;
+	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// Any call.
+	Any,
+	/// Any call that can't move funds out of the proxied account, nor escalate to root.
+	NonTransfer,
+	/// Governance calls, i.e. sudo and the treasury.
+	Governance,
+	/// Only calls to the template pallet.
+	Template,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(
+				c,
+				RuntimeCall::Balances(..) |
+					// Scheduled calls run with the proxied origin but without the proxy filter.
+					RuntimeCall::Scheduler(..) |
+					RuntimeCall::Tasks(..) |
+					RuntimeCall::Indices(pallet_indices::Call::transfer { .. }) |
+					RuntimeCall::Vesting(pallet_vesting::Call::vested_transfer { .. }) |
+					RuntimeCall::Sudo(..) |
+					RuntimeCall::Contracts(..) |
+					RuntimeCall::EVM(..) |
+					RuntimeCall::Ethereum(..)
+			),
+			ProxyType::Governance => matches!(
+				c,
+				RuntimeCall::Sudo(..) |
+					RuntimeCall::Treasury(..) |
+					RuntimeCall::Bounties(..) |
+					RuntimeCall::ChildBounties(..) |
+					RuntimeCall::Utility(..)
+			),
+			ProxyType::Template =>
+				matches!(c, RuntimeCall::TemplateModule(..) | RuntimeCall::Utility(..)),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, ProxyType::Template) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+	// Identity deposits are multiples of the existential deposit, so keeping an identity always
+	// costs more than keeping the account it describes.
+	pub const BasicDeposit: Balance = 1_000 * EXISTENTIAL_DEPOSIT;
+	pub const FieldDeposit: Balance = 250 * EXISTENTIAL_DEPOSIT;
+	pub const SubAccountDeposit: Balance = 200 * EXISTENTIAL_DEPOSIT;
+	pub const MaxSubAccounts: u32 = 100;
+	pub const MaxAdditionalFields: u32 = 100;
+	pub const MaxRegistrars: u32 = 20;
+}
+
+impl pallet_identity::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BasicDeposit = BasicDeposit;
+	type FieldDeposit = FieldDeposit;
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = MaxSubAccounts;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	type MaxRegistrars = MaxRegistrars;
+	/// Deposits of identities cleared by `kill_identity` are burned.
+	type Slashed = ();
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+/// The display name `who` registered, without following sub-account links.
+fn raw_display_name(who: &AccountId) -> Option<Vec<u8>> {
+	match Identity::identity(who)?.info.display {
+		pallet_identity::Data::Raw(name) => Some(name.into_inner()),
+		_ => None,
+	}
+}
+
+/// The display name of `who` as reported by `IdentityApi`: sub-accounts resolve to
+/// `<parent display name>/<sub-account name>`.
+fn display_name_of(who: &AccountId) -> Option<Vec<u8>> {
+	match Identity::super_of(who) {
+		Some((parent, pallet_identity::Data::Raw(sub_name))) => {
+			let mut name = raw_display_name(&parent)?;
+			name.push(b'/');
+			name.extend_from_slice(&sub_name);
+			Some(name)
+		},
+		Some((parent, _)) => raw_display_name(&parent),
+		None => raw_display_name(who),
+	}
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = UNIT;
+	pub const SpendPeriod: BlockNumber = DAYS;
+	// Unspent funds shrink by 1% every spend period, so they don't pile up unused.
+	pub const Burn: Permill = Permill::from_percent(1);
+	pub const MaxApprovals: u32 = 100;
+	pub const MaxBalance: Balance = Balance::max_value();
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	/// Governance is sudo for now: root approves and rejects spend proposals.
+	type ApproveOrigin = EnsureRoot<AccountId>;
+	type RejectOrigin = EnsureRoot<AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	/// Bonds of rejected proposals stay in the treasury.
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type ProposalBondMaximum = ();
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = Bounties;
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = MaxApprovals;
+	type SpendOrigin = EnsureRootWithSuccess<AccountId, MaxBalance>;
+}
+
+parameter_types! {
+	pub const BountyDepositBase: Balance = UNIT;
+	pub const BountyDepositPayoutDelay: BlockNumber = DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 14 * DAYS;
+	pub const CuratorDepositMultiplier: Permill = Permill::from_percent(50);
+	pub const CuratorDepositMin: Balance = UNIT;
+	pub const CuratorDepositMax: Balance = 100 * UNIT;
+	pub const BountyValueMinimum: Balance = 5 * UNIT;
+	pub const DataDepositPerByte: Balance = deposit(0, 1);
+	pub const MaximumReasonLength: u32 = 300;
+	pub const ChildBountyValueMinimum: Balance = UNIT;
+}
+
+impl pallet_bounties::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type CuratorDepositMultiplier = CuratorDepositMultiplier;
+	type CuratorDepositMin = CuratorDepositMin;
+	type CuratorDepositMax = CuratorDepositMax;
+	type BountyValueMinimum = BountyValueMinimum;
+	type DataDepositPerByte = DataDepositPerByte;
+	type MaximumReasonLength = MaximumReasonLength;
+	type WeightInfo = pallet_bounties::weights::SubstrateWeight<Runtime>;
+	type ChildBountyManager = ChildBounties;
+}
+
+impl pallet_child_bounties::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxActiveChildBountyCount = ConstU32<5>;
+	type ChildBountyValueMinimum = ChildBountyValueMinimum;
+	type WeightInfo = pallet_child_bounties::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	/// Scheduled calls are dispatched for free, so only root schedules calls directly. Signed
+	/// accounts go through `pallet_tasks`, which makes them pay for every run and limits what
+	/// they can schedule.
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = RootOutranksAll;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const TaskDeposit: Balance = deposit(2, 128);
+	// A handful of light tasks per account, so that no account can fill the scheduler's agendas.
+	pub MaxTaskWeight: Weight = Perbill::from_percent(1) * BlockWeights::get().max_block;
+}
+
+/// Lets signed accounts schedule calls, dispatched with their origin. Every account can keep four
+/// tasks of up to 1% of a block each, for a deposit per task. Every run pays the weight fee of its
+/// call, split with the treasury like transaction fees.
+impl pallet_tasks::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type Preimages = Preimage;
+	type Currency = Balances;
+	type WeightToFee = IdentityFee<Balance>;
+	type OnTaskFee = DealWithFees;
+	type TaskDeposit = TaskDeposit;
+	type MaxTasksPerAccount = ConstU32<4>;
+	type MaxTaskWeight = MaxTaskWeight;
+	type WeightInfo = pallet_tasks::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub const DepositPerItem: Balance = deposit(1, 0);
+	pub const DepositPerByte: Balance = deposit(0, 1);
+	pub const DeletionQueueDepth: u32 = 128;
+	// The lazy deletion runs inside on_initialize.
+	pub DeletionWeightLimit: Weight = BlockWeights::get()
+		.per_class
+		.get(DispatchClass::Normal)
+		.max_total
+		.unwrap_or(BlockWeights::get().max_block);
+	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+}
+
+impl pallet_contracts::Config for Runtime {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	/// The safest default is to allow no calls at all.
+	///
+	/// Runtimes should whitelist dispatchables that are allowed to be called from contracts
+	/// and make sure they are stable. Contracts reach the template pallet through
+	/// [`TemplateExtension`] instead.
+	type CallFilter = Nothing;
+	type DepositPerItem = DepositPerItem;
+	type DepositPerByte = DepositPerByte;
+	type CallStack = [pallet_contracts::Frame<Self>; 31];
+	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
+	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = TemplateExtension;
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type Schedule = Schedule;
+	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+	type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+	type MaxStorageKeyLen = ConstU32<128>;
+	type UnsafeUnstableInterface = ConstBool<false>;
+	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+}
+
+/// Current approximation of the gas/s consumption considering
+/// EVM execution over compiled WASM (on 4.4Ghz CPU).
+/// Given the 2 sec Weight, from which 75% only are used for transactions,
+/// the total EVM execution gas limit is: GAS_PER_SECOND * 2 * 0.75 ~= 60_000_000.
+pub const GAS_PER_SECOND: u64 = 40_000_000;
+
+/// Approximate ratio of the amount of Weight per Gas.
+/// u64 works for approximations because Weight is a very small unit compared to gas.
+pub const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
+
+/// The EVM chain id, as defined by EIP-155.
+pub const EVM_CHAIN_ID: u64 = 42;
+
+/// Fixed EVM gas price. Fees are paid in the native token.
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> (U256, Weight) {
+		(MICROUNIT.into(), Weight::zero())
+	}
+}
+
+/// Maps the index of the Aura author to a truncated 20-byte address, for the EVM `COINBASE`.
+pub struct FindAuthorTruncated<F>(PhantomData<F>);
+impl<F: FindAuthor<u32>> FindAuthor<H160> for FindAuthorTruncated<F> {
+	fn find_author<'a, I>(digests: I) -> Option<H160>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		if let Some(author_index) = F::find_author(digests) {
+			let authority_id = Aura::authorities()[author_index as usize].clone();
+			return Some(H160::from_slice(&authority_id.encode()[4..24]))
+		}
+		None
+	}
+}
+
+parameter_types! {
+	pub BlockGasLimit: U256 =
+		U256::from(NORMAL_DISPATCH_RATIO * (2u64 * WEIGHT_REF_TIME_PER_SECOND) / WEIGHT_PER_GAS);
+	pub PrecompilesValue: FrontierPrecompiles<Runtime> = FrontierPrecompiles::<_>::new();
+	pub WeightPerGas: Weight = Weight::from_ref_time(WEIGHT_PER_GAS);
+}
+
+impl pallet_evm::Config for Runtime {
+	type FeeCalculator = FixedGasPrice;
+	type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+	type WeightPerGas = WeightPerGas;
+	type BlockHashMapping = pallet_ethereum::EthereumBlockHashMapping<Self>;
+	type CallOrigin = EnsureAddressTruncated;
+	type WithdrawOrigin = EnsureAddressTruncated;
+	/// 20-byte EVM addresses are hashed into the 32-byte `AccountId` used by `MultiSignature`.
+	type AddressMapping = HashedAddressMapping<BlakeTwo256>;
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type PrecompilesType = FrontierPrecompiles<Self>;
+	type PrecompilesValue = PrecompilesValue;
+	type ChainId = ConstU64<EVM_CHAIN_ID>;
+	type BlockGasLimit = BlockGasLimit;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	/// Gas fees are split with the treasury like the fees of other transactions, priority fees
+	/// go to the block author.
+	type OnChargeTransaction = pallet_evm::EVMCurrencyAdapter<Balances, DealWithFees>;
+	type FindAuthor = FindAuthorTruncated<Aura>;
+}
+
+impl pallet_ethereum::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type StateRoot = pallet_ethereum::IntermediateStateRoot<Self>;
+}
+
+impl pallet_authorship::Config for Runtime {
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = ConstU32<0>;
+	type FilterUncle = ();
+	/// Block authors earn era points in staking, and their share of the block reward, the
+	/// chain's only source of inflation.
+	type EventHandler = (Staking, Rewards);
+}
+
+parameter_types! {
+	pub const Period: BlockNumber = HOURS;
+	pub const Offset: BlockNumber = 0;
+}
+
+impl pallet_session::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = <Self as frame_system::Config>::AccountId;
+	type ValidatorIdOf = pallet_staking::StashOf<Self>;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_session::historical::Config for Runtime {
+	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = pallet_staking::ExposureOf<Runtime>;
+}
+
+parameter_types! {
+	// Six one-hour sessions per era.
+	pub const SessionsPerEra: sp_staking::SessionIndex = 6;
+	// 28 eras, i.e. seven days, to unbond.
+	pub const BondingDuration: sp_staking::EraIndex = 28;
+	// A quarter of the bonding duration, so slashes can be cancelled before funds unbond.
+	pub const SlashDeferDuration: sp_staking::EraIndex = 7;
+	pub const MaxNominatorRewardedPerValidator: u32 = 256;
+	pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
+	pub const MaxNominations: u32 = 16;
+	// Electing more validators than there can be authorities would truncate the session keys.
+	pub const MaxActiveValidators: u32 = MAX_AUTHORITIES;
+	pub const HistoryDepth: u32 = 84;
+}
+
+/// Sequential phragmen, run on chain. Enough for the validator sets this chain targets; a
+/// multi-phase election with off-chain solutions only pays off for much larger sets.
+pub struct OnChainSeqPhragmen;
+impl onchain::Config for OnChainSeqPhragmen {
+	type System = Runtime;
+	type Solver = SequentialPhragmen<AccountId, Perbill>;
+	type DataProvider = Staking;
+	type WeightInfo = frame_election_provider_support::weights::SubstrateWeight<Runtime>;
+	type MaxWinners = MaxActiveValidators;
+	type VotersBound = ConstU32<{ 10 * 1024 }>;
+	type TargetsBound = ConstU32<1024>;
+}
+
+pub struct StakingBenchmarkingConfig;
+impl pallet_staking::BenchmarkingConfig for StakingBenchmarkingConfig {
+	type MaxNominators = ConstU32<1000>;
+	type MaxValidators = ConstU32<1000>;
+}
+
+impl pallet_staking::Config for Runtime {
+	type MaxNominations = MaxNominations;
+	type Currency = Balances;
+	type CurrencyBalance = Balance;
+	type UnixTime = Timestamp;
+	type CurrencyToVote = U128CurrencyToVote;
+	/// The whole era payout goes to stakers, there is no remainder.
+	type RewardRemainder = ();
+	type RuntimeEvent = RuntimeEvent;
+	/// Slashed funds go to the treasury.
+	type Slash = Treasury;
+	/// Rewards are minted.
+	type Reward = ();
+	type SessionsPerEra = SessionsPerEra;
+	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	type SlashCancelOrigin = EnsureRoot<AccountId>;
+	type SessionInterface = Self;
+	/// The stakers' share of the block rewards of the era, so the yearly rate stays the one of
+	/// the `Inflation` set in genesis.
+	type EraPayout = StakersPayout;
+	type NextNewSession = Session;
+	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type OffendingValidatorsThreshold = OffendingValidatorsThreshold;
+	type ElectionProvider = onchain::OnChainExecution<OnChainSeqPhragmen>;
+	type GenesisElectionProvider = onchain::OnChainExecution<OnChainSeqPhragmen>;
+	type VoterList = VoterList;
+	type TargetList = pallet_staking::UseValidatorsMap<Self>;
+	type MaxUnlockingChunks = ConstU32<32>;
+	type HistoryDepth = HistoryDepth;
+	type OnStakerSlash = ();
+	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
+	type BenchmarkingConfig = StakingBenchmarkingConfig;
+}
+
+parameter_types! {
+	pub const BagThresholds: &'static [u64] = &voter_bags::THRESHOLDS;
+}
+
+type VoterBagsListInstance = pallet_bags_list::Instance1;
+impl pallet_bags_list::Config<VoterBagsListInstance> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	/// Nominators are sorted by their bonded stake.
+	type ScoreProvider = Staking;
+	type WeightInfo = pallet_bags_list::weights::SubstrateWeight<Runtime>;
+	type BagThresholds = BagThresholds;
+	type Score = VoteWeight;
+}
+
+impl pallet_offences::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = Staking;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -298,11 +957,34 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
+		Contracts: pallet_contracts,
+		EVM: pallet_evm,
+		Ethereum: pallet_ethereum,
+		Utility: pallet_utility,
+		Multisig: pallet_multisig,
+		Proxy: pallet_proxy,
+		Vesting: pallet_vesting,
+		// Staking must come before Session, which asks it for the genesis validator set.
+		Authorship: pallet_authorship,
+		Staking: pallet_staking,
+		Session: pallet_session,
+		Historical: pallet_session::historical,
+		Offences: pallet_offences,
+		VoterList: pallet_bags_list::<Instance1>,
+		Rewards: pallet_rewards,
+		Identity: pallet_identity,
+		Indices: pallet_indices,
+		Treasury: pallet_treasury,
+		Bounties: pallet_bounties,
+		ChildBounties: pallet_child_bounties,
+		Scheduler: pallet_scheduler,
+		Preimage: pallet_preimage,
+		Tasks: pallet_tasks,
 	}
 );
 
 /// The address format for describing accounts.
-pub type Address = sp_runtime::MultiAddress<AccountId, ()>;
+pub type Address = sp_runtime::MultiAddress<AccountId, AccountIndex>;
 /// Block header type as expected by this runtime.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
@@ -320,8 +1002,14 @@ pub type SignedExtra = (
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
+///
+/// Ethereum transactions are self-contained: they carry their own signature and are dispatched
+/// with a [`pallet_ethereum::RawOrigin::EthereumTransaction`] origin.
 pub type UncheckedExtrinsic =
-	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
+	fp_self_contained::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
+/// Extrinsic type that has already been checked.
+pub type CheckedExtrinsic =
+	fp_self_contained::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra, H160>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
@@ -333,6 +1021,119 @@ pub type Executive = frame_executive::Executive<
 	AllPalletsWithSystem,
 >;
 
+/// Converts Ethereum transactions received over `eth_*` RPCs into extrinsics.
+#[derive(Clone)]
+pub struct TransactionConverter;
+
+impl fp_rpc::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
+	fn convert_transaction(&self, transaction: pallet_ethereum::Transaction) -> UncheckedExtrinsic {
+		UncheckedExtrinsic::new_unsigned(
+			pallet_ethereum::Call::<Runtime>::transact { transaction }.into(),
+		)
+	}
+}
+
+impl fp_rpc::ConvertTransaction<opaque::UncheckedExtrinsic> for TransactionConverter {
+	fn convert_transaction(
+		&self,
+		transaction: pallet_ethereum::Transaction,
+	) -> opaque::UncheckedExtrinsic {
+		let extrinsic = UncheckedExtrinsic::new_unsigned(
+			pallet_ethereum::Call::<Runtime>::transact { transaction }.into(),
+		);
+		let encoded = extrinsic.encode();
+		opaque::UncheckedExtrinsic::decode(&mut &encoded[..])
+			.expect("Encoded extrinsic is always valid")
+	}
+}
+
+impl fp_self_contained::SelfContainedCall for RuntimeCall {
+	type SignedInfo = H160;
+
+	fn is_self_contained(&self) -> bool {
+		match self {
+			RuntimeCall::Ethereum(call) => call.is_self_contained(),
+			_ => false,
+		}
+	}
+
+	fn check_self_contained(&self) -> Option<Result<Self::SignedInfo, TransactionValidityError>> {
+		match self {
+			RuntimeCall::Ethereum(call) => call.check_self_contained(),
+			_ => None,
+		}
+	}
+
+	fn validate_self_contained(
+		&self,
+		info: &Self::SignedInfo,
+		dispatch_info: &DispatchInfoOf<RuntimeCall>,
+		len: usize,
+	) -> Option<TransactionValidity> {
+		match self {
+			RuntimeCall::Ethereum(call) => call.validate_self_contained(info, dispatch_info, len),
+			_ => None,
+		}
+	}
+
+	fn pre_dispatch_self_contained(
+		&self,
+		info: &Self::SignedInfo,
+		dispatch_info: &DispatchInfoOf<RuntimeCall>,
+		len: usize,
+	) -> Option<Result<(), TransactionValidityError>> {
+		match self {
+			RuntimeCall::Ethereum(call) =>
+				call.pre_dispatch_self_contained(info, dispatch_info, len),
+			_ => None,
+		}
+	}
+
+	fn apply_self_contained(
+		self,
+		info: Self::SignedInfo,
+	) -> Option<sp_runtime::DispatchResultWithInfo<PostDispatchInfoOf<Self>>> {
+		match self {
+			call @ RuntimeCall::Ethereum(pallet_ethereum::Call::transact { .. }) => Some(
+				call.dispatch(RuntimeOrigin::from(
+					pallet_ethereum::RawOrigin::EthereumTransaction(info),
+				)),
+			),
+			_ => None,
+		}
+	}
+}
+
+/// The length of an extrinsic dispatching `call` from `origin`, for `DryRunApi` to charge the
+/// length fee of. Signed calls are measured with an sr25519 signature, a mortal era, the signer's
+/// next nonce and no tip; other origins as unsigned extrinsics.
+fn dry_run_len(origin: &frame_system::RawOrigin<AccountId>, call: &RuntimeCall) -> u32 {
+	let extrinsic = match origin {
+		frame_system::RawOrigin::Signed(who) => {
+			let extra: SignedExtra = (
+				frame_system::CheckNonZeroSender::new(),
+				frame_system::CheckSpecVersion::new(),
+				frame_system::CheckTxVersion::new(),
+				frame_system::CheckGenesis::new(),
+				// Mortal eras all encode to two bytes.
+				frame_system::CheckEra::from(generic::Era::mortal(256, 0)),
+				frame_system::CheckNonce::from(System::account_nonce(who)),
+				frame_system::CheckWeight::new(),
+				pallet_transaction_payment::ChargeTransactionPayment::from(0),
+			);
+			let signature = sp_core::sr25519::Signature::from_raw([0; 64]);
+			UncheckedExtrinsic::new_signed(
+				call.clone(),
+				Address::Id(who.clone()),
+				signature.into(),
+				extra,
+			)
+		},
+		_ => UncheckedExtrinsic::new_unsigned(call.clone()),
+	};
+	extrinsic.encoded_size() as u32
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -345,6 +1146,23 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
+		[pallet_contracts, Contracts]
+		[pallet_evm, EVM]
+		[pallet_utility, Utility]
+		[pallet_multisig, Multisig]
+		[pallet_proxy, Proxy]
+		[pallet_vesting, Vesting]
+		[pallet_staking, Staking]
+		[pallet_bags_list, VoterList]
+		[pallet_rewards, Rewards]
+		[pallet_identity, Identity]
+		[pallet_indices, Indices]
+		[pallet_treasury, Treasury]
+		[pallet_bounties, Bounties]
+		[pallet_child_bounties, ChildBounties]
+		[pallet_scheduler, Scheduler]
+		[pallet_preimage, Preimage]
+		[pallet_tasks, Tasks]
 	);
 }
 
@@ -438,23 +1256,24 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(equivocation_proof, key_owner_proof)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -496,6 +1315,298 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>
+		for Runtime
+	{
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractExecResult<Balance> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_call(
+				origin,
+				dest,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				input_data,
+				true,
+				pallet_contracts::Determinism::Deterministic,
+			)
+		}
+
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: pallet_contracts_primitives::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_instantiate(
+				origin,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data,
+				salt,
+				true,
+			)
+		}
+
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+			determinism: pallet_contracts::Determinism,
+		) -> pallet_contracts_primitives::CodeUploadResult<Hash, Balance> {
+			Contracts::bare_upload_code(origin, code, storage_deposit_limit, determinism)
+		}
+
+		fn get_storage(
+			address: AccountId,
+			key: Vec<u8>,
+		) -> pallet_contracts_primitives::GetStorageResult {
+			Contracts::get_storage(address, key)
+		}
+	}
+
+	impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
+		fn chain_id() -> u64 {
+			<Runtime as pallet_evm::Config>::ChainId::get()
+		}
+
+		fn account_basic(address: H160) -> EVMAccount {
+			let (account, _) = EVM::account_basic(&address);
+			account
+		}
+
+		fn gas_price() -> U256 {
+			let (gas_price, _) = <Runtime as pallet_evm::Config>::FeeCalculator::min_gas_price();
+			gas_price
+		}
+
+		fn account_code_at(address: H160) -> Vec<u8> {
+			pallet_evm::AccountCodes::<Runtime>::get(address)
+		}
+
+		fn author() -> H160 {
+			<pallet_evm::Pallet<Runtime>>::find_author()
+		}
+
+		fn storage_at(address: H160, index: U256) -> H256 {
+			let mut tmp = [0u8; 32];
+			index.to_big_endian(&mut tmp);
+			pallet_evm::AccountStorages::<Runtime>::get(address, H256::from_slice(&tmp[..]))
+		}
+
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
+			let config = if estimate {
+				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				config.estimate = true;
+				Some(config)
+			} else {
+				None
+			};
+
+			let is_transactional = false;
+			let validate = true;
+			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			<Runtime as pallet_evm::Config>::Runner::call(
+				from,
+				to,
+				data,
+				value,
+				gas_limit.unique_saturated_into(),
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list.unwrap_or_default(),
+				is_transactional,
+				validate,
+				evm_config,
+			).map_err(|err| err.error.into())
+		}
+
+		fn create(
+			from: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
+			let config = if estimate {
+				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				config.estimate = true;
+				Some(config)
+			} else {
+				None
+			};
+
+			let is_transactional = false;
+			let validate = true;
+			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			<Runtime as pallet_evm::Config>::Runner::create(
+				from,
+				data,
+				value,
+				gas_limit.unique_saturated_into(),
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list.unwrap_or_default(),
+				is_transactional,
+				validate,
+				evm_config,
+			).map_err(|err| err.error.into())
+		}
+
+		fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
+			pallet_ethereum::CurrentTransactionStatuses::<Runtime>::get()
+		}
+
+		fn current_block() -> Option<pallet_ethereum::Block> {
+			pallet_ethereum::CurrentBlock::<Runtime>::get()
+		}
+
+		fn current_receipts() -> Option<Vec<pallet_ethereum::Receipt>> {
+			pallet_ethereum::CurrentReceipts::<Runtime>::get()
+		}
+
+		fn current_all() -> (
+			Option<pallet_ethereum::Block>,
+			Option<Vec<pallet_ethereum::Receipt>>,
+			Option<Vec<TransactionStatus>>,
+		) {
+			(
+				pallet_ethereum::CurrentBlock::<Runtime>::get(),
+				pallet_ethereum::CurrentReceipts::<Runtime>::get(),
+				pallet_ethereum::CurrentTransactionStatuses::<Runtime>::get(),
+			)
+		}
+
+		fn extrinsic_filter(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+		) -> Vec<EthereumTransaction> {
+			xts.into_iter().filter_map(|xt| match xt.0.function {
+				RuntimeCall::Ethereum(transact { transaction }) => Some(transaction),
+				_ => None,
+			}).collect::<Vec<EthereumTransaction>>()
+		}
+
+		fn elasticity() -> Option<Permill> {
+			// The gas price is fixed, see `FixedGasPrice`.
+			None
+		}
+
+		fn gas_limit_multiplier_support() {}
+
+		fn pending_block(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+		) -> (Option<pallet_ethereum::Block>, Option<Vec<TransactionStatus>>) {
+			for ext in xts.into_iter() {
+				let _ = Executive::apply_extrinsic(ext);
+			}
+
+			Ethereum::on_finalize(System::block_number() + 1);
+
+			(
+				pallet_ethereum::CurrentBlock::<Runtime>::get(),
+				pallet_ethereum::CurrentTransactionStatuses::<Runtime>::get(),
+			)
+		}
+	}
+
+	impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
+		fn convert_transaction(transaction: EthereumTransaction) -> <Block as BlockT>::Extrinsic {
+			UncheckedExtrinsic::new_unsigned(
+				pallet_ethereum::Call::<Runtime>::transact { transaction }.into(),
+			)
+		}
+	}
+
+	impl apis::IdentityApi<Block, AccountId> for Runtime {
+		fn display_name(who: AccountId) -> Option<Vec<u8>> {
+			display_name_of(&who)
+		}
+	}
+
+	impl apis::DryRunApi<Block, RuntimeCall, RuntimeEvent, AccountId, Balance> for Runtime {
+		fn dry_run_call(
+			origin: frame_system::RawOrigin<AccountId>,
+			call: RuntimeCall,
+		) -> apis::DryRunEffects<RuntimeEvent, Balance> {
+			use frame_support::dispatch::GetDispatchInfo;
+
+			System::reset_events();
+
+			let info = call.get_dispatch_info();
+			let len = dry_run_len(&origin, &call);
+			let (result, post_info) = match call.dispatch(origin.into()) {
+				Ok(post_info) => (Ok(()), post_info),
+				Err(err) => (Err(err.error), err.post_info),
+			};
+
+			apis::DryRunEffects {
+				result,
+				events: System::events().into_iter().map(|record| record.event).collect(),
+				actual_weight: post_info.calc_actual_weight(&info),
+				fee: TransactionPayment::compute_actual_fee(len, &info, &post_info, 0),
+			}
+		}
+
+		fn dry_run_extrinsic(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> Result<apis::DryRunEffects<RuntimeEvent, Balance>, TransactionValidityError> {
+			System::reset_events();
+
+			let result = Executive::apply_extrinsic(extrinsic)?;
+			let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+
+			// Applying an extrinsic only tells whether its call succeeded, what it consumed and
+			// paid is in its events.
+			let mut actual_weight = Weight::zero();
+			let mut fee = 0;
+			for event in &events {
+				match event {
+					RuntimeEvent::System(frame_system::Event::ExtrinsicSuccess { dispatch_info }) |
+					RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+						dispatch_info,
+						..
+					}) => actual_weight = dispatch_info.weight,
+					RuntimeEvent::TransactionPayment(
+						pallet_transaction_payment::Event::TransactionFeePaid { actual_fee, .. },
+					) => fee = *actual_fee,
+					_ => {},
+				}
+			}
+
+			Ok(apis::DryRunEffects { result, events, actual_weight, fee })
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -563,10 +1674,26 @@ impl_runtime_apis! {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::traits::WhitelistedStorageKeys;
+	use frame_support::{
+		assert_err, assert_noop, assert_ok,
+		dispatch::{DispatchInfo, GetDispatchInfo},
+		traits::{
+			Currency, GenesisBuild, OnFinalize, OnInitialize, OnUnbalanced, PrivilegeCmp,
+			WhitelistedStorageKeys,
+		},
+		weights::WeightToFee,
+	};
 	use sp_core::hexdisplay::HexDisplay;
 	use std::collections::HashSet;
 
+	fn new_test_ext(balances: Vec<(AccountId, Balance)>) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> { balances }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		t.into()
+	}
+
 	#[test]
 	fn check_whitelist() {
 		let whitelist: HashSet<String> = AllPalletsWithSystem::whitelisted_storage_keys()
@@ -595,4 +1722,383 @@ mod tests {
 			whitelist.contains("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
 		);
 	}
+
+	#[test]
+	fn proxy_type_filters_calls() {
+		let transfer: RuntimeCall =
+			BalancesCall::transfer { dest: AccountId::new([1; 32]).into(), value: 1 }.into();
+		let sudo: RuntimeCall =
+			pallet_sudo::Call::sudo { call: Box::new(transfer.clone()) }.into();
+		let template: RuntimeCall = pallet_template::Call::do_something { something: 42 }.into();
+		let remark: RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+
+		assert!(ProxyType::Any.filter(&transfer));
+		assert!(!ProxyType::NonTransfer.filter(&transfer));
+		assert!(!ProxyType::NonTransfer.filter(&sudo));
+		assert!(ProxyType::NonTransfer.filter(&template));
+		assert!(ProxyType::NonTransfer.filter(&remark));
+		assert!(ProxyType::Governance.filter(&sudo));
+		assert!(!ProxyType::Governance.filter(&template));
+		assert!(ProxyType::Template.filter(&template));
+		assert!(!ProxyType::Template.filter(&transfer));
+		assert!(!ProxyType::Template.filter(&remark));
+
+		assert!(ProxyType::Any.is_superset(&ProxyType::Template));
+		assert!(ProxyType::NonTransfer.is_superset(&ProxyType::Template));
+		assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::Governance));
+		assert!(!ProxyType::Template.is_superset(&ProxyType::Any));
+	}
+
+	#[test]
+	fn voter_bags_cover_all_scores() {
+		let thresholds = voter_bags::THRESHOLDS;
+		assert_eq!(thresholds[0], voter_bags::EXISTENTIAL_WEIGHT);
+		assert_eq!(thresholds[thresholds.len() - 1], VoteWeight::MAX);
+		assert!(thresholds.windows(2).all(|w| w[0] < w[1]));
+	}
+
+	/// Register an identity for `who`, with `display` as its only field.
+	fn set_identity(who: &AccountId, display: pallet_identity::Data) {
+		use pallet_identity::Data;
+
+		let info = pallet_identity::IdentityInfo::<MaxAdditionalFields> {
+			additional: Default::default(),
+			display,
+			legal: Data::None,
+			web: Data::None,
+			riot: Data::None,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		};
+		assert_ok!(Identity::set_identity(RuntimeOrigin::signed(who.clone()), Box::new(info)));
+	}
+
+	fn raw(name: &[u8]) -> pallet_identity::Data {
+		pallet_identity::Data::Raw(name.to_vec().try_into().unwrap())
+	}
+
+	#[test]
+	fn display_names_are_raw_identity_data() {
+		let alice = AccountId::new([1; 32]);
+		let bob = AccountId::new([2; 32]);
+		let charlie = AccountId::new([3; 32]);
+		let dave = AccountId::new([4; 32]);
+		let balances = [&alice, &bob, &charlie, &dave].map(|who| (who.clone(), 10 * UNIT));
+
+		new_test_ext(balances.to_vec()).execute_with(|| {
+			set_identity(&alice, raw(b"alice"));
+			assert_eq!(display_name_of(&alice), Some(b"alice".to_vec()));
+
+			// Names are returned as they are stored, UTF-8 or not.
+			set_identity(&bob, raw(&[0xff, 0xfe]));
+			assert_eq!(display_name_of(&bob), Some(vec![0xff, 0xfe]));
+
+			// Only raw names are reported: hashes and empty names aren't.
+			set_identity(&charlie, pallet_identity::Data::BlakeTwo256([0; 32]));
+			assert_eq!(display_name_of(&charlie), None);
+			set_identity(&charlie, pallet_identity::Data::None);
+			assert_eq!(display_name_of(&charlie), None);
+
+			assert_eq!(display_name_of(&dave), None);
+		});
+	}
+
+	#[test]
+	fn sub_accounts_are_named_after_their_parent() {
+		let alice = AccountId::new([1; 32]);
+		let bot = AccountId::new([2; 32]);
+		let unnamed = AccountId::new([3; 32]);
+		let hashed = AccountId::new([4; 32]);
+
+		new_test_ext(vec![(alice.clone(), 10 * UNIT)]).execute_with(|| {
+			set_identity(&alice, raw(b"alice"));
+			assert_ok!(Identity::set_subs(
+				RuntimeOrigin::signed(alice.clone()),
+				vec![
+					(bot.clone(), raw(b"bot")),
+					(unnamed.clone(), pallet_identity::Data::None),
+					(hashed.clone(), pallet_identity::Data::BlakeTwo256([0; 32])),
+				],
+			));
+
+			assert_eq!(display_name_of(&bot), Some(b"alice/bot".to_vec()));
+			assert_eq!(display_name_of(&unnamed), Some(b"alice".to_vec()));
+			assert_eq!(display_name_of(&hashed), Some(b"alice".to_vec()));
+
+			// Without a raw parent name, sub-accounts have no name either.
+			set_identity(&alice, pallet_identity::Data::None);
+			assert_eq!(display_name_of(&bot), None);
+		});
+	}
+
+	#[test]
+	fn transfers_can_target_account_indices() {
+		let alice = AccountId::new([1; 32]);
+		let bob = AccountId::new([2; 32]);
+
+		new_test_ext(vec![(alice.clone(), 10 * UNIT), (bob.clone(), 10 * UNIT)]).execute_with(|| {
+			assert_ok!(Indices::claim(RuntimeOrigin::signed(bob.clone()), 7));
+			assert_eq!(Indices::lookup_address(Address::Index(7)), Some(bob.clone()));
+
+			assert_ok!(Balances::transfer(RuntimeOrigin::signed(alice), Address::Index(7), UNIT));
+			assert_eq!(Balances::free_balance(&bob), 11 * UNIT - IndexDeposit::get());
+		});
+	}
+
+	#[test]
+	fn fees_are_split_with_the_treasury() {
+		new_test_ext(vec![]).execute_with(|| {
+			let issuance = Balances::total_issuance();
+
+			let fees = Balances::issue(1_000 * EXISTENTIAL_DEPOSIT);
+			let tips = Balances::issue(100 * EXISTENTIAL_DEPOSIT);
+			DealWithFees::on_unbalanceds(vec![fees, tips].into_iter());
+
+			// 80% of the fees go to the treasury. There is no block author here, so the rest of
+			// the fees and the tips are burned.
+			let to_treasury = 800 * EXISTENTIAL_DEPOSIT;
+			assert_eq!(Balances::free_balance(&Treasury::account_id()), to_treasury);
+			assert_eq!(Balances::total_issuance(), issuance + to_treasury);
+
+			FeesToTreasury::set(&Perbill::from_percent(50));
+			DealWithFees::on_unbalanceds(vec![Balances::issue(1_000)].into_iter());
+			assert_eq!(Balances::free_balance(&Treasury::account_id()), to_treasury + 500);
+		});
+	}
+
+	#[test]
+	fn evm_fees_are_split_with_the_treasury() {
+		use pallet_evm::{AddressMapping, OnChargeEVMTransaction};
+		type Charge = <Runtime as pallet_evm::Config>::OnChargeTransaction;
+
+		let who = H160::repeat_byte(1);
+		let account = <Runtime as pallet_evm::Config>::AddressMapping::into_account_id(who);
+
+		new_test_ext(vec![(account.clone(), UNIT)]).execute_with(|| {
+			let fee = U256::from(1_000 * EXISTENTIAL_DEPOSIT);
+			let paid = Charge::withdraw_fee(&who, fee).unwrap();
+			let tip = Charge::correct_and_deposit_fee(&who, fee, fee, paid);
+			Charge::pay_priority_fee(tip);
+
+			// 80% of the gas fees go to the treasury, as for other transactions.
+			assert_eq!(Balances::free_balance(&account), UNIT - 1_000 * EXISTENTIAL_DEPOSIT);
+			assert_eq!(Balances::free_balance(&Treasury::account_id()), 800 * EXISTENTIAL_DEPOSIT);
+		});
+	}
+
+	#[test]
+	fn nominators_are_paid_at_the_end_of_an_era() {
+		let validator = AccountId::new([1; 32]);
+		let nominator = AccountId::new([2; 32]);
+		let stake = 1_000 * UNIT;
+
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(validator.clone(), 2 * stake), (nominator.clone(), 2 * stake)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let inflation = Inflation::Fixed(Perbill::from_percent(10));
+		GenesisBuild::<Runtime>::assimilate_storage(
+			&pallet_rewards::GenesisConfig { inflation },
+			&mut t,
+		)
+		.unwrap();
+		pallet_staking::GenesisConfig::<Runtime> {
+			validator_count: 1,
+			minimum_validator_count: 1,
+			stakers: vec![
+				(validator.clone(), validator.clone(), stake, StakerStatus::Validator),
+				(
+					nominator.clone(),
+					nominator.clone(),
+					stake,
+					StakerStatus::Nominator(vec![validator.clone()]),
+				),
+			],
+			..Default::default()
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let keys = opaque::SessionKeys {
+			aura: sp_core::sr25519::Public::from_raw([1; 32]).into(),
+			grandpa: sp_core::ed25519::Public::from_raw([1; 32]).into(),
+		};
+		pallet_session::GenesisConfig::<Runtime> {
+			keys: vec![(validator.clone(), validator.clone(), keys)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			let before = Balances::free_balance(&nominator);
+			let issuance = Balances::total_issuance();
+			Staking::reward_by_ids(vec![(validator.clone(), 1)]);
+
+			for n in 1..=SessionsPerEra::get() * Period::get() {
+				System::set_block_number(n);
+				pallet_timestamp::Now::<Runtime>::put(n as u64 * MILLISECS_PER_BLOCK);
+				Session::on_initialize(n);
+				Staking::on_finalize(n);
+			}
+			assert_eq!(Staking::active_era().map(|era| era.index), Some(1));
+
+			// The stakers' share of the block rewards of every block but the first, which
+			// started the era.
+			let payout = Staking::eras_validator_reward(0).unwrap();
+			let blocks = SessionsPerEra::get() * Period::get() - 1;
+			assert_eq!(payout, Rewards::stakers_reward(blocks));
+			assert!(payout > 0);
+
+			assert_ok!(Staking::payout_stakers(
+				RuntimeOrigin::signed(nominator.clone()),
+				validator.clone(),
+				0,
+			));
+			assert!(Balances::free_balance(&nominator) > before);
+			assert!(Balances::total_issuance() <= issuance + payout);
+		});
+	}
+
+	#[test]
+	fn signed_accounts_schedule_periodic_calls() {
+		let alice = AccountId::new([1; 32]);
+		let bob = AccountId::new([2; 32]);
+		let task = *b"alice/pay-bob-every-three-blocks";
+		let pay_bob: RuntimeCall =
+			BalancesCall::transfer_keep_alive { dest: bob.clone().into(), value: UNIT }.into();
+
+		new_test_ext(vec![(alice.clone(), 10 * UNIT)]).execute_with(|| {
+			// Only root may use the scheduler directly.
+			assert_noop!(
+				Scheduler::schedule_named(
+					RuntimeOrigin::signed(alice.clone()),
+					task,
+					2,
+					Some((3, 3)),
+					0,
+					Box::new(pay_bob.clone()),
+				),
+				sp_runtime::DispatchError::BadOrigin
+			);
+
+			// Pay Bob three times, every three blocks from block 2.
+			assert_ok!(Tasks::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				task,
+				2,
+				Some((3, 3)),
+				Box::new(pay_bob.clone()),
+			));
+			assert_eq!(Balances::reserved_balance(&alice), TaskDeposit::get());
+
+			// Only Alice may cancel her task.
+			assert_noop!(
+				Tasks::cancel(RuntimeOrigin::signed(bob.clone()), task),
+				pallet_tasks::Error::<Runtime>::UnknownTask
+			);
+
+			for n in 1..=9 {
+				System::set_block_number(n);
+				Scheduler::on_initialize(n);
+			}
+			assert_eq!(Balances::free_balance(&bob), 3 * UNIT);
+			// Every run paid the fee of the transfer.
+			let fee = IdentityFee::<Balance>::weight_to_fee(&pay_bob.get_dispatch_info().weight);
+			assert_eq!(Balances::free_balance(&alice), 7 * UNIT - TaskDeposit::get() - 3 * fee);
+			assert_ok!(Tasks::cancel(RuntimeOrigin::signed(alice.clone()), task));
+			assert_eq!(Balances::reserved_balance(&alice), 0);
+
+			// Root may cancel any task, the deposit stays reserved until Alice cancels it too.
+			assert_ok!(Tasks::schedule(
+				RuntimeOrigin::signed(alice.clone()),
+				task,
+				10,
+				Some((3, 2)),
+				Box::new(pay_bob),
+			));
+			let scheduled = Tasks::scheduler_id(&alice, &task);
+			assert_ok!(Scheduler::cancel_named(RuntimeOrigin::root(), scheduled));
+			assert_eq!(Balances::reserved_balance(&alice), TaskDeposit::get());
+		});
+	}
+
+	#[test]
+	fn root_outranks_signed_origins_for_the_scheduler() {
+		use sp_std::cmp::Ordering;
+
+		let root = OriginCaller::system(frame_system::RawOrigin::Root);
+		let alice = OriginCaller::system(frame_system::RawOrigin::Signed(AccountId::new([1; 32])));
+		let bob = OriginCaller::system(frame_system::RawOrigin::Signed(AccountId::new([2; 32])));
+
+		assert_eq!(RootOutranksAll::cmp_privilege(&root, &alice), Some(Ordering::Greater));
+		assert_eq!(RootOutranksAll::cmp_privilege(&alice, &root), Some(Ordering::Less));
+		assert_eq!(RootOutranksAll::cmp_privilege(&alice, &alice), Some(Ordering::Equal));
+		assert_eq!(RootOutranksAll::cmp_privilege(&alice, &bob), None);
+	}
+
+	#[test]
+	fn block_weights_limit_proof_size() {
+		let weights = BlockWeights::get();
+		assert_ok!(weights.clone().validate());
+		assert_eq!(weights.max_block.proof_size(), MAX_POV_SIZE);
+
+		for class in [DispatchClass::Normal, DispatchClass::Operational, DispatchClass::Mandatory] {
+			let limits = weights.get(class);
+			if let Some(max_total) = limits.max_total {
+				assert!(max_total.proof_size() <= MAX_POV_SIZE);
+			}
+			if let Some(max_extrinsic) = limits.max_extrinsic {
+				assert!(max_extrinsic.proof_size() <= MAX_POV_SIZE);
+			}
+		}
+	}
+
+	#[test]
+	fn blocks_respect_both_weight_dimensions() {
+		use frame_system::CheckWeight;
+		use sp_runtime::transaction_validity::InvalidTransaction;
+
+		let max_extrinsic = BlockWeights::get()
+			.get(DispatchClass::Normal)
+			.max_extrinsic
+			.expect("normal is limited");
+		let info = |ref_time, proof_size| DispatchInfo {
+			weight: Weight::from_parts(ref_time, proof_size),
+			..Default::default()
+		};
+		let exhausted = TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources);
+
+		let too_slow = info(max_extrinsic.ref_time() + 1, 0);
+		let too_big = info(0, max_extrinsic.proof_size() + 1);
+		let biggest = info(0, max_extrinsic.proof_size());
+		let compute_only = info(1_000, 0);
+
+		new_test_ext(vec![]).execute_with(|| {
+			// Too much of either dimension for a single extrinsic.
+			assert_err!(CheckWeight::<Runtime>::do_pre_dispatch(&too_slow, 0), exhausted);
+			assert_err!(CheckWeight::<Runtime>::do_pre_dispatch(&too_big, 0), exhausted);
+
+			// The biggest possible proof fits once, but the block then has no room for another...
+			assert_ok!(CheckWeight::<Runtime>::do_pre_dispatch(&biggest, 0));
+			assert_err!(CheckWeight::<Runtime>::do_pre_dispatch(&biggest, 0), exhausted);
+			// ...while a compute-only extrinsic still fits.
+			assert_ok!(CheckWeight::<Runtime>::do_pre_dispatch(&compute_only, 0));
+		});
+	}
+
+	#[test]
+	fn dry_runs_charge_the_length_of_a_signed_extrinsic() {
+		let remark: RuntimeCall = SystemCall::remark { remark: vec![] }.into();
+		let signed = frame_system::RawOrigin::Signed(AccountId::new([1; 32]));
+
+		new_test_ext(vec![]).execute_with(|| {
+			// The length prefix, the version byte and the call.
+			assert_eq!(dry_run_len(&frame_system::RawOrigin::Root, &remark), 1 + 1 + 3);
+			// Then the address, signature, era, nonce and tip too, behind a longer prefix.
+			assert_eq!(dry_run_len(&signed, &remark), 2 + 1 + 33 + 65 + 2 + 1 + 1 + 3);
+		});
+	}
 }
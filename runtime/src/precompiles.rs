@@ -0,0 +1,140 @@
+//! EVM precompiles available on this chain.
+//!
+//! Besides the standard Ethereum precompiles, the set includes [`TemplatePrecompile`] which lets
+//! Solidity contracts and Ethereum accounts reach `pallet_template`.
+
+use fp_evm::{ExitError, ExitSucceed, PrecompileFailure, PrecompileOutput};
+use frame_support::{dispatch::GetDispatchInfo, traits::Get};
+use pallet_evm::{
+	AddressMapping, GasWeightMapping, Precompile, PrecompileHandle, PrecompileResult,
+	PrecompileSet,
+};
+use pallet_evm_precompile_simple::{ECRecover, Identity, Ripemd160, Sha256};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Address of [`TemplatePrecompile`].
+pub const TEMPLATE_PRECOMPILE_ADDRESS: u64 = 1024;
+
+/// The set of precompiles exposed to the EVM.
+pub struct FrontierPrecompiles<R>(PhantomData<R>);
+
+impl<R> FrontierPrecompiles<R>
+where
+	R: pallet_evm::Config,
+{
+	pub fn new() -> Self {
+		Self(Default::default())
+	}
+
+	pub fn used_addresses() -> [H160; 5] {
+		[hash(1), hash(2), hash(3), hash(4), hash(TEMPLATE_PRECOMPILE_ADDRESS)]
+	}
+}
+
+impl<R> PrecompileSet for FrontierPrecompiles<R>
+where
+	R: pallet_evm::Config + pallet_template::Config,
+{
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		match handle.code_address() {
+			// Ethereum precompiles :
+			a if a == hash(1) => Some(ECRecover::execute(handle)),
+			a if a == hash(2) => Some(Sha256::execute(handle)),
+			a if a == hash(3) => Some(Ripemd160::execute(handle)),
+			a if a == hash(4) => Some(Identity::execute(handle)),
+			// Runtime precompiles :
+			a if a == hash(TEMPLATE_PRECOMPILE_ADDRESS) =>
+				Some(TemplatePrecompile::<R>::execute(handle)),
+			_ => None,
+		}
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		Self::used_addresses().contains(&address)
+	}
+}
+
+fn hash(a: u64) -> H160 {
+	H160::from_low_u64_be(a)
+}
+
+/// Solidity interface of [`TemplatePrecompile`]:
+///
+/// ```solidity
+/// interface Template {
+///     function doSomething(uint32 something) external;
+///     function something() external view returns (uint32);
+/// }
+/// ```
+pub struct TemplatePrecompile<R>(PhantomData<R>);
+
+/// `bytes4(keccak256("doSomething(uint32)"))`
+const SELECTOR_DO_SOMETHING: [u8; 4] = [0x3e, 0x2a, 0xef, 0x0a];
+/// `bytes4(keccak256("something()"))`
+const SELECTOR_SOMETHING: [u8; 4] = [0xa7, 0xa0, 0xd5, 0x37];
+
+impl<R> Precompile for TemplatePrecompile<R>
+where
+	R: pallet_evm::Config + pallet_template::Config,
+{
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let input = handle.input().to_vec();
+		if input.len() < 4 {
+			return Err(revert("missing function selector"))
+		}
+		let (selector, args) = input.split_at(4);
+
+		match selector {
+			s if s == SELECTOR_DO_SOMETHING => {
+				if handle.is_static() {
+					return Err(revert("cannot modify state in static context"))
+				}
+				let something = decode_u32(args)?;
+
+				let call = pallet_template::Call::<R>::do_something { something };
+				let weight = call.get_dispatch_info().weight;
+				handle.record_cost(R::GasWeightMapping::weight_to_gas(weight))?;
+
+				// The EVM caller is mapped to the substrate account signing the call.
+				let who = R::AddressMapping::into_account_id(handle.context().caller);
+				pallet_template::Pallet::<R>::do_something(
+					frame_system::RawOrigin::Signed(who).into(),
+					something,
+				)
+				.map_err(|e| revert(<&'static str>::from(e)))?;
+
+				Ok(PrecompileOutput { exit_status: ExitSucceed::Stopped, output: Vec::new() })
+			},
+			s if s == SELECTOR_SOMETHING => {
+				handle.record_cost(R::GasWeightMapping::weight_to_gas(
+					<R as frame_system::Config>::DbWeight::get().reads(1),
+				))?;
+
+				let something = pallet_template::Something::<R>::get().unwrap_or_default();
+				let mut output = [0u8; 32];
+				U256::from(something).to_big_endian(&mut output);
+
+				Ok(PrecompileOutput { exit_status: ExitSucceed::Returned, output: output.to_vec() })
+			},
+			_ => Err(revert("unknown function selector")),
+		}
+	}
+}
+
+/// Decode a single ABI encoded `uint32` argument.
+fn decode_u32(args: &[u8]) -> Result<u32, PrecompileFailure> {
+	if args.len() != 32 {
+		return Err(revert("expected a single uint32 argument"))
+	}
+	let value = U256::from_big_endian(args);
+	if value > U256::from(u32::MAX) {
+		return Err(revert("value out of bounds for uint32"))
+	}
+
+	Ok(value.low_u32())
+}
+
+fn revert(message: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error { exit_status: ExitError::Other(message.into()) }
+}
@@ -0,0 +1,218 @@
+//! Autogenerated voter bag thresholds.
+//!
+//! Generated for an existential weight of `EXISTENTIAL_DEPOSIT` (500) and 200 bags, with the
+//! same geometric progression as `substrate/utils/frame/generate-bags`.
+
+/// Existential weight for this runtime.
+#[cfg(any(test, feature = "std"))]
+#[allow(unused)]
+pub const EXISTENTIAL_WEIGHT: u64 = 500;
+
+/// Constant ratio between bags for this runtime.
+#[cfg(any(test, feature = "std"))]
+#[allow(unused)]
+pub const CONSTANT_RATIO: f64 = 1.2112980096490553;
+
+/// Upper thresholds delimiting the bag list.
+pub const THRESHOLDS: [u64; 200] = [
+	500,
+	605,
+	732,
+	886,
+	1_073,
+	1_299,
+	1_573,
+	1_905,
+	2_307,
+	2_794,
+	3_384,
+	4_099,
+	4_965,
+	6_014,
+	7_284,
+	8_823,
+	10_687,
+	12_945,
+	15_680,
+	18_993,
+	23_006,
+	27_867,
+	33_755,
+	40_887,
+	49_526,
+	59_990,
+	72_665,
+	88_018,
+	106_616,
+	129_143,
+	156_430,
+	189_483,
+	229_520,
+	278_017,
+	336_761,
+	407_917,
+	494_109,
+	598_513,
+	724_977,
+	878_163,
+	1_063_717,
+	1_288_478,
+	1_560_730,
+	1_890_509,
+	2_289_969,
+	2_773_834,
+	3_359_939,
+	4_069_887,
+	4_929_846,
+	5_971_512,
+	7_233_280,
+	8_761_657,
+	10_612_977,
+	12_855_477,
+	15_571_813,
+	18_862_106,
+	22_847_631,
+	27_675_289,
+	33_523_022,
+	40_606_369,
+	49_186_413,
+	59_579_404,
+	72_168_413,
+	87_417_455,
+	105_888_589,
+	128_262_637,
+	155_364_276,
+	188_192_438,
+	227_957_125,
+	276_124_011,
+	334_468_464,
+	405_140_984,
+	490_746_467,
+	594_440_218,
+	720_044_252,
+	872_188_169,
+	1_056_479_793,
+	1_279_711_870,
+	1_550_112_441,
+	1_877_648_114,
+	2_274_391_423,
+	2_754_965_803,
+	3_337_084_593,
+	4_042_203_925,
+	4_896_313_568,
+	5_930_894_879,
+	7_184_081_162,
+	8_702_063_212,
+	10_540_791_848,
+	12_768_040_185,
+	15_465_901_663,
+	18_733_815_901,
+	22_692_233_914,
+	27_487_057_774,
+	33_295_018_372,
+	40_330_189_485,
+	48_851_878_251,
+	59_174_182_893,
+	71_677_569_960,
+	86_822_897_829,
+	105_168_403_332,
+	127_390_277_634,
+	154_307_589_746,
+	186_912_476_333,
+	226_406_710_560,
+	274_245_997_872,
+	332_193_631_376,
+	402_385_484_503,
+	487_408_736_490,
+	590_397_232_395,
+	715_146_992_502,
+	866_256_128_624,
+	1_049_294_324_448,
+	1_271_008_126_739,
+	1_539_569_614_166,
+	1_864_877_609_355,
+	2_258_922_536_450,
+	2_736_228_372_353,
+	3_314_387_981_376,
+	4_014_711_565_045,
+	4_863_012_128_054,
+	5_890_556_911_611,
+	7_135_219_862_758,
+	8_642_877_618_167,
+	10_469_100_456_526,
+	12_681_200_545_805,
+	15_360_712_981_094,
+	18_606_401_060_789,
+	22_537_896_571_665,
+	27_300_109_258_934,
+	33_068_568_008_548,
+	40_055_890_610_698,
+	48_519_620_571_458,
+	58_771_719_827_134,
+	71_190_067_250_259,
+	86_232_386_767_021,
+	104_453_118_458_180,
+	126_523_854_490_030,
+	153_258_093_116_900,
+	185_641_223_155_110,
+	224_866_844_116_600,
+	272_380_760_714_501,
+	329_934_273_320_170,
+	399_648_728_587_729,
+	484_093_709_497_091,
+	586_381_746_797_454,
+	710_283_042_790_292,
+	860_364_436_019_355,
+	1_042_157_728_923_076,
+	1_262_363_582_784_901,
+	1_529_098_495_280_800,
+	1_852_193_963_890_998,
+	2_243_558_861_945_159,
+	2_717_618_384_004_670,
+	3_291_845_739_530_538,
+	3_987_406_192_365_063,
+	4_829_937_184_474_118,
+	5_850_493_298_283_460,
+	7_086_690_887_675_891,
+	8_584_094_567_239_903,
+	10_397_896_663_936_962,
+	12_594_951_533_563_393,
+	15_256_239_724_231_654,
+	18_479_852_812_690_654,
+	22_384_608_930_619_684,
+	27_114_432_244_432_090,
+	32_843_657_810_444_756,
+	39_783_457_335_386_380,
+	48_189_622_687_311_629,
+	58_371_994_046_879_533,
+	70_705_880_208_231_680,
+	85_645_891_966_715_562,
+	103_742_698_473_900_570,
+	125_663_324_177_057_841,
+	152_215_734_461_554_166,
+	184_378_616_190_549_669,
+	223_337_450_813_459_887,
+	270_528_209_650_437_736,
+	327_690_281_903_497_570,
+	396_930_586_251_044_434,
+	480_801_229_094_722_777,
+	582_393_571_839_257_133,
+	705_452_174_401_296_238,
+	854_512_814_754_888_336,
+	1_035_069_671_732_208_092,
+	1_253_777_833_217_324_646,
+	1_518_698_593_918_250_491,
+	1_839_596_584_069_995_622,
+	2_228_299_680_841_186_632,
+	2_699_134_968_304_554_416,
+	3_269_456_814_881_472_588,
+	3_960_286_532_499_467_424,
+	4_797_087_194_456_563_445,
+	5_810_702_170_758_205_761,
+	7_038_491_974_102_859_403,
+	8_525_711_319_161_643_317,
+	10_327_177_151_742_919_782,
+	12_509_289_129_199_398_150,
+	15_152_477_024_323_794_514,
+	18_446_744_073_709_551_615,
+];